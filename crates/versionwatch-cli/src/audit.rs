@@ -0,0 +1,116 @@
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use versionwatch_collect::registry::{PackageReleases, RegistryClient};
+use versionwatch_core::domain::dependency::{self, DependencyStatus, Ecosystem};
+use versionwatch_scan::find_files;
+use versionwatch_scan::lockfiles::{self, LockedDependency};
+
+/// Registry lookups running at the same time.
+const CONCURRENT_LOOKUPS: usize = 8;
+
+/// A locked dependency and how stale it is.
+#[derive(Serialize, Debug, Clone)]
+pub struct AuditEntry {
+    pub file: PathBuf,
+    pub line: usize,
+    #[serde(flatten)]
+    pub status: DependencyStatus,
+}
+
+/// Reads every lockfile found under the given paths.
+pub fn lockfiles(paths: &[PathBuf]) -> Result<Vec<LockedDependency>> {
+    let mut dependencies = Vec::new();
+    for file in find_files(paths, lockfiles::is_lockfile) {
+        dependencies.extend(lockfiles::scan_file(&file)?);
+    }
+    Ok(dependencies)
+}
+
+/// Looks up each package once in its registry and evaluates every locked version.
+/// Packages whose lookup fails are reported without registry data.
+pub async fn evaluate(dependencies: Vec<LockedDependency>) -> Vec<AuditEntry> {
+    let client = RegistryClient::new();
+    let mut packages: Vec<(Ecosystem, &str)> = dependencies
+        .iter()
+        .map(|d| (d.dependency.ecosystem, d.dependency.name.as_str()))
+        .collect();
+    packages.sort_unstable();
+    packages.dedup();
+
+    let client = &client;
+    let releases: HashMap<(Ecosystem, String), PackageReleases> = stream::iter(packages)
+        .map(|(ecosystem, name)| async move {
+            match client.releases(ecosystem, name).await {
+                Ok(releases) => Some(((ecosystem, name.to_string()), releases)),
+                Err(e) => {
+                    eprintln!(
+                        "❌ Failed to look up {} package {name}: {e}",
+                        ecosystem.label()
+                    );
+                    None
+                }
+            }
+        })
+        .buffer_unordered(CONCURRENT_LOOKUPS)
+        .filter_map(|result| async move { result })
+        .collect()
+        .await;
+
+    let today = chrono::Utc::now().date_naive();
+    let entries: Vec<AuditEntry> = dependencies
+        .iter()
+        .map(|locked| {
+            let key = (locked.dependency.ecosystem, locked.dependency.name.clone());
+            let package = releases.get(&key).cloned().unwrap_or_default();
+            AuditEntry {
+                file: locked.file.clone(),
+                line: locked.line,
+                status: dependency::evaluate(
+                    &locked.dependency,
+                    &package.releases,
+                    package.latest.as_deref(),
+                    today,
+                ),
+            }
+        })
+        .collect();
+
+    // Some registries list releases without dates; look those up one by one.
+    stream::iter(entries)
+        .map(|mut entry| async move {
+            let dependency = &entry.status.dependency;
+            if entry.status.published.is_some() || entry.status.latest.is_none() {
+                return entry;
+            }
+            let published = client
+                .published(dependency.ecosystem, &dependency.name, &dependency.version)
+                .await;
+            if let Ok(Some(published)) = published {
+                entry.status.published = Some(published);
+                entry.status.age_days = Some((today - published).num_days());
+            }
+            entry
+        })
+        .buffered(CONCURRENT_LOOKUPS)
+        .collect()
+        .await
+}
+
+/// Prints how many dependencies are outdated.
+pub fn print_summary(entries: &[AuditEntry]) {
+    let outdated = entries
+        .iter()
+        .filter(|e| e.status.versions_behind.unwrap_or(0) > 0)
+        .count();
+    let major = entries
+        .iter()
+        .filter(|e| e.status.majors_behind.unwrap_or(0) > 0)
+        .count();
+    println!(
+        "\n{} dependencies, {outdated} outdated, {major} behind a major version",
+        entries.len()
+    );
+}
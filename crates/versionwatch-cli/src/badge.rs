@@ -0,0 +1,281 @@
+use crate::dashboard::AppState;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use versionwatch_core::domain::lifecycle::{is_behind, Lifecycle, SupportStatus};
+
+const COLOR_BLUE: &str = "#007ec6";
+const COLOR_GREEN: &str = "#4c1";
+const COLOR_YELLOW: &str = "#dfb317";
+const COLOR_ORANGE: &str = "#fe7d37";
+const COLOR_RED: &str = "#e05d44";
+const COLOR_GREY: &str = "#9f9f9f";
+
+const LABEL_COLOR: &str = "#555";
+const FONT_FAMILY: &str = "Verdana,Geneva,DejaVu Sans,sans-serif";
+
+/// Badges follow the collection interval, so caches may keep them for as long.
+const BADGE_CACHE_CONTROL: &str = "public, max-age=300";
+
+/// Visual style of a badge, following the shields.io naming.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BadgeStyle {
+    #[default]
+    Flat,
+    FlatSquare,
+    ForTheBadge,
+}
+
+/// A two-part status badge: a grey label on the left, a colored message on the right.
+#[derive(Debug, Clone)]
+pub struct Badge {
+    pub label: String,
+    pub message: String,
+    pub color: &'static str,
+}
+
+impl Badge {
+    pub fn new(label: impl Into<String>, message: impl Into<String>, color: &'static str) -> Self {
+        Self {
+            label: label.into(),
+            message: message.into(),
+            color,
+        }
+    }
+
+    pub fn render(&self, style: BadgeStyle) -> String {
+        match style {
+            BadgeStyle::Flat => self.render_flat(true),
+            BadgeStyle::FlatSquare => self.render_flat(false),
+            BadgeStyle::ForTheBadge => self.render_for_the_badge(),
+        }
+    }
+
+    fn render_flat(&self, rounded: bool) -> String {
+        let label_width = text_width(&self.label) + 10;
+        let message_width = text_width(&self.message) + 10;
+        let width = label_width + message_width;
+        let radius = if rounded { 3 } else { 0 };
+        let label = escape(&self.label);
+        let message = escape(&self.message);
+        let gradient = if rounded {
+            r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##
+        } else {
+            ""
+        };
+        let overlay = if rounded {
+            format!(r#"<rect width="{width}" height="20" fill="url(#s)"/>"#)
+        } else {
+            String::new()
+        };
+
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">"#,
+                r#"<title>{label}: {message}</title>{gradient}"#,
+                r##"<clipPath id="r"><rect width="{width}" height="20" rx="{radius}" fill="#fff"/></clipPath>"##,
+                r#"<g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="{label_color}"/>"#,
+                r#"<rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>{overlay}</g>"#,
+                r##"<g fill="#fff" text-anchor="middle" font-family="{font}" font-size="11">"##,
+                r##"<text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>"##,
+                r#"<text x="{label_x}" y="14">{label}</text>"#,
+                r##"<text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>"##,
+                r#"<text x="{message_x}" y="14">{message}</text></g></svg>"#,
+            ),
+            width = width,
+            label = label,
+            message = message,
+            gradient = gradient,
+            radius = radius,
+            label_width = label_width,
+            label_color = LABEL_COLOR,
+            message_width = message_width,
+            color = self.color,
+            overlay = overlay,
+            font = FONT_FAMILY,
+            label_x = label_width as f64 / 2.0,
+            message_x = label_width as f64 + message_width as f64 / 2.0,
+        )
+    }
+
+    fn render_for_the_badge(&self) -> String {
+        let label_text = self.label.to_uppercase();
+        let message_text = self.message.to_uppercase();
+        // Upper-case text with letter spacing is roughly 20% wider than the flat style.
+        let label_width = text_width(&label_text) * 6 / 5 + 20;
+        let message_width = text_width(&message_text) * 6 / 5 + 20;
+        let width = label_width + message_width;
+        let label = escape(&label_text);
+        let message = escape(&message_text);
+
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="28" role="img" aria-label="{label}: {message}">"#,
+                r#"<title>{label}: {message}</title>"#,
+                r#"<g shape-rendering="crispEdges"><rect width="{label_width}" height="28" fill="{label_color}"/>"#,
+                r#"<rect x="{label_width}" width="{message_width}" height="28" fill="{color}"/></g>"#,
+                r##"<g fill="#fff" text-anchor="middle" font-family="{font}" font-size="10" letter-spacing="1">"##,
+                r#"<text x="{label_x}" y="18">{label}</text>"#,
+                r#"<text x="{message_x}" y="18" font-weight="bold">{message}</text></g></svg>"#,
+            ),
+            width = width,
+            label = label,
+            message = message,
+            label_width = label_width,
+            label_color = LABEL_COLOR,
+            message_width = message_width,
+            color = self.color,
+            font = FONT_FAMILY,
+            label_x = label_width as f64 / 2.0,
+            message_x = label_width as f64 + message_width as f64 / 2.0,
+        )
+    }
+}
+
+/// Approximates the rendered width of a string in 11px Verdana.
+fn text_width(text: &str) -> u32 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '!' | '|' | '\'' => 4,
+            ' ' | 'f' | 'r' | 't' | '(' | ')' | '[' | ']' | '-' | '/' => 5,
+            'm' | 'w' | 'M' | 'W' | '@' | '%' => 10,
+            'A'..='Z' => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct BadgeQuery {
+    #[serde(default)]
+    pub style: BadgeStyle,
+}
+
+/// `GET /badge/{product}.svg` - latest known version of a product.
+pub async fn product_badge(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+    Query(query): Query<BadgeQuery>,
+) -> Response {
+    let Some(product) = file.strip_suffix(".svg") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    with_lifecycle(&state, product, query.style, |lifecycle| {
+        lifecycle
+            .latest()
+            .map(|cycle| Badge::new(product, cycle.latest.clone(), COLOR_BLUE))
+    })
+    .await
+}
+
+/// `GET /badge/{product}/{cycle}.svg` - support status of a release cycle.
+pub async fn cycle_badge(
+    State(state): State<AppState>,
+    Path((product, file)): Path<(String, String)>,
+    Query(query): Query<BadgeQuery>,
+) -> Response {
+    let Some(cycle_name) = file.strip_suffix(".svg") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let today = chrono::Utc::now().date_naive();
+
+    with_lifecycle(&state, &product, query.style, |lifecycle| {
+        let cycle = lifecycle.cycle_for_version(cycle_name)?;
+        let status = lifecycle.status(cycle, today);
+        Some(Badge::new(
+            format!("{product} {cycle_name}"),
+            status.label(),
+            status_color(status),
+        ))
+    })
+    .await
+}
+
+/// `GET /badge/{product}/{version}/status.svg` - whether a deployed version is current.
+pub async fn version_badge(
+    State(state): State<AppState>,
+    Path((product, version)): Path<(String, String)>,
+    Query(query): Query<BadgeQuery>,
+) -> Response {
+    let today = chrono::Utc::now().date_naive();
+
+    with_lifecycle(&state, &product, query.style, |lifecycle| {
+        let label = format!("{product} {version}");
+        let Some(cycle) = lifecycle.cycle_for_version(&version) else {
+            return Some(Badge::new(label, "unknown", COLOR_GREY));
+        };
+
+        let badge = match lifecycle.status(cycle, today) {
+            SupportStatus::Eol => Badge::new(label, "EOL", COLOR_RED),
+            _ if is_behind(&version, &cycle.latest) => Badge::new(
+                label,
+                format!("outdated ({} available)", cycle.latest),
+                COLOR_ORANGE,
+            ),
+            SupportStatus::SecurityOnly => Badge::new(label, "security-only", COLOR_YELLOW),
+            _ => Badge::new(label, "up to date", COLOR_GREEN),
+        };
+        Some(badge)
+    })
+    .await
+}
+
+/// Builds a badge from the stored cycles of a product, answering with a grey
+/// placeholder when the product is unknown or nothing was collected yet.
+async fn with_lifecycle<F>(state: &AppState, product: &str, style: BadgeStyle, build: F) -> Response
+where
+    F: FnOnce(&Lifecycle) -> Option<Badge>,
+{
+    let cycles = state.cycles.read().await;
+
+    if cycles.is_empty() {
+        let badge = Badge::new(product, "no data yet", COLOR_GREY);
+        return svg_response(StatusCode::SERVICE_UNAVAILABLE, "no-cache", &badge, style);
+    }
+
+    let badge = cycles
+        .get(product)
+        .map(|rows| Lifecycle::new(product, rows))
+        .and_then(|lifecycle| build(&lifecycle));
+
+    match badge {
+        Some(badge) => svg_response(StatusCode::OK, BADGE_CACHE_CONTROL, &badge, style),
+        None => {
+            let badge = Badge::new(product, "not found", COLOR_GREY);
+            svg_response(StatusCode::NOT_FOUND, BADGE_CACHE_CONTROL, &badge, style)
+        }
+    }
+}
+
+fn svg_response(
+    status: StatusCode,
+    cache_control: &'static str,
+    badge: &Badge,
+    style: BadgeStyle,
+) -> Response {
+    let headers = [
+        (header::CONTENT_TYPE, "image/svg+xml;charset=utf-8"),
+        (header::CACHE_CONTROL, cache_control),
+    ];
+    (status, headers, badge.render(style)).into_response()
+}
+
+fn status_color(status: SupportStatus) -> &'static str {
+    match status {
+        SupportStatus::Supported => COLOR_GREEN,
+        SupportStatus::SecurityOnly => COLOR_YELLOW,
+        SupportStatus::Eol => COLOR_RED,
+        SupportStatus::Unknown => COLOR_GREY,
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use versionwatch_scan::check::{CheckResult, Severity};
+use versionwatch_scan::Finding;
+
+use crate::scan;
+
+/// Runs every repository scanner over the given paths.
+pub fn scan_all(paths: &[PathBuf]) -> Result<Vec<Finding>> {
+    let mut findings = scan::dockerfiles(paths, &[])?;
+    findings.extend(scan::manifests(paths)?);
+    findings.extend(scan::runtimes(paths)?);
+    findings.extend(scan::workflows(paths)?);
+    Ok(findings)
+}
+
+/// Prints warnings, failures and waived violations followed by a one-line verdict.
+pub fn print_summary(results: &[CheckResult]) {
+    for result in results
+        .iter()
+        .filter(|r| r.severity != Severity::Pass || r.waiver.is_some())
+    {
+        let icon = match (result.severity, &result.waiver) {
+            (_, Some(_)) => "🔕",
+            (Severity::Fail, _) => "❌",
+            _ => "⚠️ ",
+        };
+        println!(
+            "{icon} {}:{} {} [{}]",
+            result.report.finding.file.display(),
+            result.report.finding.line,
+            result.message.as_deref().unwrap_or_default(),
+            result.rule.as_deref().unwrap_or_default(),
+        );
+        match (&result.waiver, &result.remediation) {
+            (Some(waiver), _) => println!(
+                "   ↳ waived until {}: {}",
+                waiver.expires, waiver.justification
+            ),
+            (None, Some(remediation)) => println!("   ↳ {remediation}"),
+            (None, None) => {}
+        }
+    }
+
+    // A policy reports every violation of a reference separately.
+    let reference = |r: &CheckResult| {
+        let finding = &r.report.finding;
+        (
+            finding.file.clone(),
+            finding.line,
+            finding.reference.clone(),
+        )
+    };
+    let references: HashSet<_> = results.iter().map(reference).collect();
+    let flagged: HashSet<_> = results
+        .iter()
+        .filter(|r| r.severity != Severity::Pass)
+        .map(reference)
+        .collect();
+    let count = |severity| results.iter().filter(|r| r.severity == severity).count();
+    let waived = results.iter().filter(|r| r.waiver.is_some()).count();
+    let overall = versionwatch_scan::check::overall(results);
+    println!(
+        "\n{} references checked: {} passed, {} warnings, {} failures, {waived} waived → {}",
+        references.len(),
+        references.len() - flagged.len(),
+        count(Severity::Warn),
+        count(Severity::Fail),
+        overall.label().to_uppercase()
+    );
+}
+
+pub fn write_sarif(path: &Path, results: &[CheckResult]) -> Result<()> {
+    let sarif = versionwatch_scan::check::sarif(results);
+    std::fs::write(path, serde_json::to_string_pretty(&sarif)?)
+        .with_context(|| format!("Could not write {}", path.display()))
+}
+
+pub fn write_junit(path: &Path, results: &[CheckResult]) -> Result<()> {
+    std::fs::write(path, versionwatch_scan::check::junit(results))
+        .with_context(|| format!("Could not write {}", path.display()))
+}
@@ -0,0 +1,185 @@
+use crate::dashboard::CycleStore;
+use futures::future::join_all;
+use versionwatch_collect::{
+    apache::ApacheCollector, caddy::CaddyCollector, docker::DockerCollector,
+    eclipse_temurin::EclipseTemurinCollector, go::GoCollector, kong::KongCollector,
+    kotlin::KotlinCollector, limits::CollectionLimits, mongodb::MongoDbCollector,
+    mysql::MySqlCollector, nginx::NginxCollector, node::NodeCollector, perl::PerlCollector,
+    php::PhpCollector, postgresql::PostgresqlCollector, python::PythonCollector,
+    ruby::RubyCollector, rust::RustCollector, scala::ScalaCollector, swift::SwiftCollector,
+    validate::validate, Collector,
+};
+use versionwatch_config::{Settings, Target};
+use versionwatch_core::domain::product_cycle::{EolSource, ProductCycle};
+use versionwatch_core::domain::provenance::{Provenance, Sourced};
+use versionwatch_core::domain::quarantine::QuarantinedRow;
+use versionwatch_core::domain::support::{self, LtsRule, SupportPolicy};
+
+/// Collects the given products on demand, for commands that need lifecycle data
+/// without a running dashboard. Products that fail to collect are left out.
+pub async fn collect_products<'a>(
+    config: &Settings,
+    products: impl IntoIterator<Item = &'a str>,
+) -> CycleStore {
+    let collectors: Vec<_> = products
+        .into_iter()
+        .filter_map(|name| {
+            let target = target_for(config, name);
+            create_collector(&target, config.github_token.as_deref()).map(|c| (target.name, c))
+        })
+        .collect();
+
+    let limits = CollectionLimits::new(&config.collection);
+    let results = join_all(collectors.iter().map(|(name, collector)| {
+        let limits = &limits;
+        async move {
+            let timeout = config.collection.timeout(&target_for(config, name));
+            let collection = limits.run(collector.as_ref(), timeout).await;
+            let fetched = Sourced {
+                source: collector.source().to_string(),
+                fetched_at: collection.started_at,
+            };
+            (name.clone(), fetched, collection.result)
+        }
+    }))
+    .await;
+
+    let today = chrono::Utc::now().date_naive();
+    let mut store = CycleStore::new();
+    for (name, fetched, result) in results {
+        match result.and_then(|df| validate(&name, &df, &config.validation, today)) {
+            Ok(mut validated) => {
+                report_quarantine(&validated.quarantined);
+                infer(&target_for(config, &name), &mut validated.rows, &fetched);
+                store.insert(name, validated.rows);
+            }
+            Err(e) => eprintln!("❌ Failed to collect {name}: {e}"),
+        }
+    }
+    store
+}
+
+/// Prints the rows validation held back.
+pub fn report_quarantine(rows: &[QuarantinedRow]) {
+    for row in rows {
+        eprintln!(
+            "⚠️  {} {:?}: quarantined ({}): {}",
+            row.product,
+            row.name,
+            row.reason.label(),
+            row.detail
+        );
+    }
+}
+
+/// Returns the configured target for a product, or a default one so that
+/// products can be looked up even when they are disabled in the config.
+pub fn target_for(config: &Settings, name: &str) -> Target {
+    config
+        .targets
+        .iter()
+        .find(|t| t.name == name)
+        .cloned()
+        .unwrap_or_else(|| Target {
+            name: name.to_string(),
+            enabled: true,
+            repository: None,
+            github_source: "releases".to_string(),
+            cleaning: Default::default(),
+            support: None,
+            lts: None,
+            sources: Vec::new(),
+            source_mode: Default::default(),
+            timeout_secs: None,
+            schedule: None,
+        })
+}
+
+/// Fills in what sources leave out: the provenance of rows from single-source
+/// collectors, which were `fetched` from the collector's source, then, from the
+/// product's declared policies set in the config or built in, LTS flags from
+/// its LTS rule and EOL dates from its support policy.
+pub fn infer(target: &Target, rows: &mut [ProductCycle], fetched: &Sourced) {
+    let now = chrono::Utc::now();
+    for row in rows.iter_mut().filter(|r| r.provenance.is_none()) {
+        row.provenance = Some(Provenance::single(&fetched.source, fetched.fetched_at));
+    }
+
+    let lts = target
+        .lts
+        .clone()
+        .or_else(|| LtsRule::builtin(&target.name));
+    if let Some(rule) = lts {
+        support::infer_lts(rows, &rule);
+    }
+    let policy = target
+        .support
+        .clone()
+        .or_else(|| SupportPolicy::builtin(&target.name));
+    if let Some(policy) = policy {
+        let inferred = support::infer_eol(&target.name, rows, &policy);
+        let undated = rows.iter().filter(|r| r.release_date.is_none()).count();
+        if inferred == 0 && undated > 0 && rows.iter().any(|r| r.eol_date.is_none()) {
+            eprintln!(
+                "⚠️  {}: support policy inferred no EOL dates, {undated} of {} rows have no release date",
+                target.name,
+                rows.len()
+            );
+        }
+        let inferred = rows
+            .iter_mut()
+            .filter(|r| r.eol_source == EolSource::Inferred);
+        for row in inferred {
+            if let Some(provenance) = row.provenance.as_mut() {
+                let sourced = Sourced {
+                    source: "support_policy".to_string(),
+                    fetched_at: now,
+                };
+                provenance.fields.insert("eol_date".to_string(), sourced);
+            }
+        }
+    }
+}
+
+pub fn create_collector(
+    target: &versionwatch_config::Target,
+    github_token: Option<&str>,
+) -> Option<Box<dyn Collector + Send + Sync>> {
+    match target.name.as_str() {
+        "apache" => Some(Box::new(ApacheCollector::new())),
+        "docker" => from_target(target, DockerCollector::from_target(target, github_token)),
+        "eclipse-temurin" => Some(Box::new(EclipseTemurinCollector::new(&target.name))),
+        "go" => Some(Box::new(GoCollector::new(&target.name))),
+        "mongodb" => Some(Box::new(MongoDbCollector::new(&target.name))),
+        "mysql" => from_target(target, MySqlCollector::from_target(target, github_token)),
+        "node" => Some(Box::new(NodeCollector::new(&target.name))),
+        "perl" => Some(Box::new(PerlCollector)),
+        "php" => Some(Box::new(PhpCollector::new(&target.name))),
+        "postgresql" => Some(Box::new(PostgresqlCollector::new(&target.name))),
+        "swift" => from_target(target, SwiftCollector::from_target(target, github_token)),
+        "kong" => from_target(target, KongCollector::from_target(target, github_token)),
+        "caddy" => from_target(target, CaddyCollector::from_target(target, github_token)),
+        "kotlin" => Some(Box::new(KotlinCollector::new(&target.name))),
+        "nginx" => Some(Box::new(NginxCollector::new(&target.name))),
+        "python" => Some(Box::new(PythonCollector::new(&target.name))),
+        "ruby" => Some(Box::new(RubyCollector::new(&target.name))),
+        "rust" => Some(Box::new(RustCollector::new(&target.name))),
+        "scala" => Some(Box::new(ScalaCollector::new(&target.name))),
+        _ => None,
+    }
+}
+
+/// Boxes a collector built from the target's source chain, reporting chains
+/// that cannot be built.
+fn from_target<C: Collector + Send + Sync + 'static>(
+    target: &Target,
+    collector: Result<C, versionwatch_collect::Error>,
+) -> Option<Box<dyn Collector + Send + Sync>> {
+    match collector {
+        Ok(collector) => Some(Box::new(collector)),
+        Err(e) => {
+            eprintln!("⚠️  {}: {e}", target.name);
+            None
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use serde::Serialize;
+use versionwatch_core::domain::lifecycle::{Cycle, Lifecycle, SupportStatus};
+use versionwatch_core::domain::product_cycle::ProductCycle;
+
+/// A release cycle of a product and its support status.
+#[derive(Serialize, Debug, Clone)]
+pub struct CycleReport {
+    pub product: String,
+    #[serde(flatten)]
+    pub cycle: Cycle,
+    pub status: SupportStatus,
+}
+
+/// Folds collected rows into cycles, newest first.
+pub fn reports(product: &str, rows: &[ProductCycle], today: chrono::NaiveDate) -> Vec<CycleReport> {
+    let lifecycle = Lifecycle::new(product, rows);
+    lifecycle
+        .cycles()
+        .iter()
+        .map(|cycle| CycleReport {
+            product: product.to_string(),
+            cycle: cycle.clone(),
+            status: lifecycle.status(cycle, today),
+        })
+        .collect()
+}
@@ -1,24 +1,126 @@
 use anyhow::Result;
-use axum::{extract::State, response::IntoResponse, routing::get, Router};
+use axum::{
+    extract::{Path, Request, State},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::time::{interval, Duration};
+use tokio::time::Duration;
 use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
-use versionwatch_collect::{
-    apache::ApacheCollector, caddy::CaddyCollector, docker::DockerCollector,
-    eclipse_temurin::EclipseTemurinCollector, go::GoCollector, kong::KongCollector,
-    kotlin::KotlinCollector, mongodb::MongoDbCollector, mysql::MySqlCollector,
-    nginx::NginxCollector, node::NodeCollector, perl::PerlCollector, php::PhpCollector,
-    postgresql::PostgresqlCollector, python::PythonCollector, ruby::RubyCollector,
-    rust::RustCollector, scala::ScalaCollector, swift::SwiftCollector, Collector,
-};
-use versionwatch_config::Settings;
+use versionwatch_collect::limits::CollectionLimits;
+use versionwatch_collect::validate::validate;
+use versionwatch_collect::{analytics, bundle, query, Collector, Error as CollectError};
+use versionwatch_config::{Settings, Target};
+use versionwatch_core::domain::collector_run::{CollectorRun, RunStatus};
+use versionwatch_core::domain::inventory::InventoryEntry;
+use versionwatch_core::domain::product_cycle::ProductCycle;
+use versionwatch_core::domain::provenance::{Conflict, Sourced};
+use versionwatch_core::domain::quarantine::QuarantinedRow;
+use versionwatch_core::domain::regression::{self, RunSummary};
+use versionwatch_db::Db;
+
+use crate::badge;
+use crate::collectors::{self, create_collector};
+use crate::inventory;
+use crate::policy;
+use crate::scheduler;
+
+const FALLBACK_MESSAGE: &str = "every live source failed, serving fallback data";
+
+/// Runs kept per target when there is no database to record them.
+const MAX_RUN_HISTORY: usize = 100;
+/// Runs `GET /api/collectors/:name/runs` returns by default, and at most.
+const DEFAULT_RUN_LIMIT: usize = 100;
+const MAX_RUN_LIMIT: usize = 1000;
+
+/// Latest collected cycles, keyed by target name.
+pub type CycleStore = HashMap<String, Vec<ProductCycle>>;
+
+/// Rows the latest collection held back, keyed by target name.
+pub type QuarantineStore = HashMap<String, Vec<QuarantinedRow>>;
+
+/// The last accepted run of each target and the runs held back from
+/// replacing it because their source degraded.
+#[derive(Default)]
+pub struct RunGuard {
+    pub baselines: HashMap<String, RunSummary>,
+    pub degraded: HashMap<String, DegradedRun>,
+}
+
+/// A run held back because it returned much less than the last accepted one.
+/// Its rows replace the accepted data only once acknowledged.
+#[derive(Serialize, Clone)]
+pub struct DegradedRun {
+    pub product: String,
+    pub reasons: Vec<String>,
+    pub accepted: RunSummary,
+    pub current: RunSummary,
+    pub detected_at: String,
+    #[serde(skip)]
+    rows: Vec<ProductCycle>,
+}
+
+/// What collecting one target produced.
+pub struct TargetRun {
+    run: CollectorRun,
+    /// Rows validation held back, unless collection failed
+    quarantined: Option<Vec<QuarantinedRow>>,
+    /// Rows of an accepted run, and the summary that becomes its baseline
+    accepted: Option<(Vec<ProductCycle>, RunSummary)>,
+    degraded: Option<DegradedRun>,
+}
 
 #[derive(Clone)]
 pub struct AppState {
     pub config: Arc<Settings>,
-    pub metrics: Arc<tokio::sync::RwLock<DashboardMetrics>>,
+    /// Recent runs of each target, oldest first, when there is no database
+    pub runs: Arc<tokio::sync::RwLock<HashMap<String, Vec<CollectorRun>>>>,
+    pub cycles: Arc<tokio::sync::RwLock<CycleStore>>,
+    pub quarantine: Arc<tokio::sync::RwLock<QuarantineStore>>,
+    pub guard: Arc<tokio::sync::RwLock<RunGuard>>,
+    /// Concurrency limits every collection runs under
+    pub limits: Arc<CollectionLimits>,
+    /// Targets being collected, which are not collected again until done
+    pub running: Arc<std::sync::Mutex<HashSet<String>>>,
+    /// When the scheduler next collects each enabled target
+    pub next_runs: Arc<tokio::sync::RwLock<HashMap<String, DateTime<Utc>>>>,
+    /// Permits for `POST /api/query`, held until a query finishes
+    pub queries: Arc<tokio::sync::Semaphore>,
+    pub db: Option<Db>,
+}
+
+impl AppState {
+    /// State with the accepted data and baselines recorded in the database,
+    /// so new runs are compared with them.
+    pub async fn new(config: &Settings, db: Option<Db>) -> Result<Self> {
+        let mut guard = RunGuard::default();
+        let mut cycles = CycleStore::new();
+        if let Some(db) = &db {
+            guard.baselines.extend(db.list_baselines().await?);
+            for (product, row) in db.list_cycles().await? {
+                cycles.entry(product).or_default().push(row);
+            }
+        }
+        Ok(AppState {
+            config: Arc::new(config.clone()),
+            runs: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            cycles: Arc::new(tokio::sync::RwLock::new(cycles)),
+            quarantine: Arc::new(tokio::sync::RwLock::new(QuarantineStore::new())),
+            guard: Arc::new(tokio::sync::RwLock::new(guard)),
+            limits: Arc::new(CollectionLimits::new(&config.collection)),
+            running: Arc::new(std::sync::Mutex::new(HashSet::new())),
+            next_runs: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            queries: Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_QUERIES)),
+            db,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -26,7 +128,11 @@ pub struct DashboardMetrics {
     pub total_collectors: usize,
     pub active_collectors: usize,
     pub failed_collectors: usize,
+    /// Failed collectors whose latest run timed out
+    pub timed_out_collectors: usize,
     pub total_versions: usize,
+    /// Rows held back by validation in the latest collection
+    pub quarantined_rows: usize,
     pub last_updated: String,
     pub collector_stats: Vec<CollectorMetric>,
     pub system_health: SystemHealth,
@@ -41,6 +147,12 @@ pub struct CollectorMetric {
     pub performance_category: String,
     pub response_time: f64,
     pub error_message: Option<String>,
+    /// Sources the collected versions came from, e.g. "github_tags+dockerhub"
+    pub source: Option<String>,
+    /// Rows held back by validation
+    pub quarantined: usize,
+    /// When the scheduler next collects the target
+    pub next_run: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -62,23 +174,43 @@ impl Default for SystemHealth {
     }
 }
 
-pub async fn start_server(host: &str, port: u16, config: &Settings) -> Result<()> {
-    let app_state = AppState {
-        config: Arc::new(config.clone()),
-        metrics: Arc::new(tokio::sync::RwLock::new(DashboardMetrics::default())),
-    };
+pub async fn start_server(host: &str, port: u16, config: &Settings, db: Option<Db>) -> Result<()> {
+    let app_state = AppState::new(config, db).await?;
 
-    // Start background metrics collection
-    let metrics_handle = app_state.metrics.clone();
-    let config_handle = app_state.config.clone();
-    tokio::spawn(async move {
-        collect_metrics_periodically(metrics_handle, config_handle).await;
-    });
+    // Start scheduled collection
+    tokio::spawn(scheduler::collect_on_schedule(app_state.clone()));
+
+    // Routes that start work or change data: no CORS, and a token is required
+    let actions = Router::new()
+        .route("/api/query", post(post_query))
+        .route("/api/collectors/:name/run", post(run_collector))
+        .route(
+            "/api/collectors/:name/acknowledge",
+            post(acknowledge_degraded),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            require_api_token,
+        ));
 
     let app = Router::new()
         // API routes (doivent être avant les fichiers statiques)
         .route("/api/metrics", get(get_metrics))
         .route("/api/health", get(health_check))
+        .route("/api/inventory", get(get_inventory))
+        .route("/api/policy", get(get_policy_violations))
+        .route("/api/analytics/cadence", get(get_cadence))
+        .route("/api/provenance/:product", get(get_provenance))
+        .route("/api/conflicts", get(get_conflicts))
+        .route("/api/quarantine", get(get_quarantine))
+        .route("/api/collectors/:name/runs", get(get_collector_runs))
+        // Status badges
+        .route("/badge/:product", get(badge::product_badge))
+        .route("/badge/:product/:version", get(badge::cycle_badge))
+        .route(
+            "/badge/:product/:version/status.svg",
+            get(badge::version_badge),
+        )
         // Servir les fichiers statiques depuis frontend/dist
         .nest_service("/assets", ServeDir::new("frontend/dist/assets"))
         .route("/vite.svg", get(serve_vite_svg))
@@ -86,6 +218,7 @@ pub async fn start_server(host: &str, port: u16, config: &Settings) -> Result<()
         // Route de fallback pour servir index.html pour toutes les autres routes
         .fallback(serve_index)
         .layer(CorsLayer::permissive())
+        .merge(actions)
         .with_state(app_state);
 
     let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
@@ -98,13 +231,222 @@ pub async fn start_server(host: &str, port: u16, config: &Settings) -> Result<()
     Ok(())
 }
 
+/// Lets a request through when it carries the configured `api_token` as a
+/// bearer token.
+async fn require_api_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(token) = state.config.api_token.as_deref().filter(|t| !t.is_empty()) else {
+        return (
+            axum::http::StatusCode::FORBIDDEN,
+            "Set api_token in the configuration to enable this endpoint",
+        )
+            .into_response();
+    };
+    let authorized = request
+        .headers()
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| given == token);
+    if !authorized {
+        return (
+            axum::http::StatusCode::UNAUTHORIZED,
+            "Missing or wrong API token",
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
 async fn get_metrics(State(state): State<AppState>) -> impl IntoResponse {
-    let metrics = state.metrics.read().await;
-    axum::Json(metrics.clone())
+    axum::Json(current_metrics(&state).await)
+}
+
+/// Metrics from the latest run of each target, recorded in the database when
+/// it is configured.
+async fn current_metrics(state: &AppState) -> DashboardMetrics {
+    let latest = match &state.db {
+        Some(db) => db.latest_runs().await.unwrap_or_else(|e| {
+            eprintln!("❌ Failed to load collector runs: {e}");
+            Vec::new()
+        }),
+        None => {
+            let runs = state.runs.read().await;
+            runs.values().filter_map(|r| r.last().cloned()).collect()
+        }
+    };
+    let mut metrics = dashboard_metrics(&state.config, &latest);
+    let next_runs = state.next_runs.read().await;
+    for stat in &mut metrics.collector_stats {
+        stat.next_run = next_runs.get(&stat.name).map(|at| at.to_rfc3339());
+    }
+    metrics
+}
+
+/// Collects a target now, streaming its progress as newline-delimited JSON:
+/// a `started` event, then a `completed` event with the run once it is
+/// stored. The collection finishes even if the client disconnects.
+async fn run_collector(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let Some(target) = state.config.targets.iter().find(|t| t.name == name) else {
+        return (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("Unknown collector: {name}"),
+        )
+            .into_response();
+    };
+    let Some(collector) = create_collector(target, state.config.github_token.as_deref()) else {
+        return (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No collector implements {name}"),
+        )
+            .into_response();
+    };
+    let (runs, busy) = scheduler::run_targets(&state, vec![(target.clone(), collector)]);
+    if !busy.is_empty() {
+        return (
+            axum::http::StatusCode::CONFLICT,
+            format!("{name} is already being collected"),
+        )
+            .into_response();
+    }
+
+    let started = serde_json::json!({
+        "event": "started",
+        "target": name,
+        "at": Utc::now().to_rfc3339(),
+    });
+    let completed = futures::stream::unfold(runs, |mut runs| async move {
+        let run = runs.recv().await?;
+        Some((
+            serde_json::json!({ "event": "completed", "run": run }),
+            runs,
+        ))
+    });
+    let events = futures::stream::once(async { started })
+        .chain(completed)
+        .map(|event| Ok::<_, std::convert::Infallible>(format!("{event}\n")));
+    (
+        [(axum::http::header::CONTENT_TYPE, "application/x-ndjson")],
+        axum::body::Body::from_stream(events),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+struct RunsParams {
+    limit: Option<usize>,
+}
+
+/// A target's recent runs with their success rate and durations, overall and
+/// per day.
+#[derive(Serialize)]
+struct RunHistory {
+    target: String,
+    success_rate: f64,
+    average_duration_ms: f64,
+    daily: Vec<DailyRuns>,
+    /// Newest first
+    runs: Vec<CollectorRun>,
+}
+
+#[derive(Serialize)]
+struct DailyRuns {
+    date: chrono::NaiveDate,
+    runs: usize,
+    success_rate: f64,
+    average_duration_ms: f64,
+}
+
+impl RunHistory {
+    fn new(target: &str, runs: Vec<CollectorRun>) -> Self {
+        let (success_rate, average_duration_ms) = run_stats(&runs);
+        let mut days: std::collections::BTreeMap<chrono::NaiveDate, Vec<CollectorRun>> =
+            Default::default();
+        for run in &runs {
+            let day = days.entry(run.started_at.date_naive()).or_default();
+            day.push(run.clone());
+        }
+        let daily = days
+            .into_iter()
+            .rev()
+            .map(|(date, runs)| {
+                let (success_rate, average_duration_ms) = run_stats(&runs);
+                DailyRuns {
+                    date,
+                    runs: runs.len(),
+                    success_rate,
+                    average_duration_ms,
+                }
+            })
+            .collect();
+        Self {
+            target: target.to_string(),
+            success_rate,
+            average_duration_ms,
+            daily,
+            runs,
+        }
+    }
+}
+
+/// Success rate, in percent, and mean duration of runs.
+fn run_stats(runs: &[CollectorRun]) -> (f64, f64) {
+    if runs.is_empty() {
+        return (0.0, 0.0);
+    }
+    let succeeded = runs.iter().filter(|r| r.status.succeeded()).count();
+    let duration: u64 = runs.iter().map(|r| r.duration_ms).sum();
+    (
+        100.0 * succeeded as f64 / runs.len() as f64,
+        duration as f64 / runs.len() as f64,
+    )
+}
+
+async fn get_collector_runs(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<RunsParams>,
+) -> impl IntoResponse {
+    if !state.config.targets.iter().any(|t| t.name == name) {
+        return (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("Unknown collector: {name}"),
+        )
+            .into_response();
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_RUN_LIMIT)
+        .clamp(1, MAX_RUN_LIMIT);
+
+    let runs = match &state.db {
+        Some(db) => match db.list_runs(&name, limit as i64).await {
+            Ok(runs) => runs,
+            Err(e) => {
+                return (
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to load runs: {e}"),
+                )
+                    .into_response()
+            }
+        },
+        None => {
+            let runs = state.runs.read().await;
+            let history = runs.get(&name).map(Vec::as_slice).unwrap_or_default();
+            history.iter().rev().take(limit).cloned().collect()
+        }
+    };
+    axum::Json(RunHistory::new(&name, runs)).into_response()
 }
 
 async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
-    let metrics = state.metrics.read().await;
+    let metrics = current_metrics(&state).await;
     axum::Json(serde_json::json!({
         "status": "healthy",
         "active_collectors": metrics.active_collectors,
@@ -113,6 +455,274 @@ async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
     }))
 }
 
+async fn get_inventory(State(state): State<AppState>) -> impl IntoResponse {
+    let entries = match load_inventory(&state).await {
+        Ok(entries) => entries,
+        Err(response) => return response,
+    };
+
+    let cycles = state.cycles.read().await;
+    let today = chrono::Utc::now().date_naive();
+    axum::Json(inventory::evaluate_all(&entries, &cycles, today)).into_response()
+}
+
+/// Policy violations of the inventory, exceptions included.
+async fn get_policy_violations(State(state): State<AppState>) -> impl IntoResponse {
+    let policy = match policy::load(&state.config, None) {
+        Ok(Some(policy)) => policy,
+        Ok(None) => {
+            return (
+                axum::http::StatusCode::NOT_FOUND,
+                "No policy configured; set `policy` in config.yaml",
+            )
+                .into_response()
+        }
+        Err(e) => {
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("{e:#}"),
+            )
+                .into_response()
+        }
+    };
+    let entries = match load_inventory(&state).await {
+        Ok(entries) => entries,
+        Err(response) => return response,
+    };
+
+    let cycles = state.cycles.read().await;
+    let today = chrono::Utc::now().date_naive();
+    axum::Json(policy::evaluate_inventory(
+        &policy, &entries, &cycles, today,
+    ))
+    .into_response()
+}
+
+/// Collected rows of a product with where each of them and their fields come from.
+async fn get_provenance(
+    State(state): State<AppState>,
+    Path(product): Path<String>,
+) -> impl IntoResponse {
+    match state.cycles.read().await.get(&product) {
+        Some(rows) => axum::Json(rows.clone()).into_response(),
+        None => (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No data collected for {product}"),
+        )
+            .into_response(),
+    }
+}
+
+/// A field sources disagreed on, for `/api/conflicts`.
+#[derive(Serialize)]
+struct ConflictReport {
+    product: String,
+    version: String,
+    #[serde(flatten)]
+    conflict: Conflict,
+}
+
+/// Every disagreement between sources in the latest collected data.
+async fn get_conflicts(State(state): State<AppState>) -> impl IntoResponse {
+    let cycles = state.cycles.read().await;
+    let mut reports: Vec<ConflictReport> = cycles
+        .iter()
+        .flat_map(|(product, rows)| {
+            rows.iter().flat_map(move |row| {
+                row.provenance
+                    .iter()
+                    .flat_map(|p| p.conflicts.iter())
+                    .map(move |conflict| ConflictReport {
+                        product: product.clone(),
+                        version: row.name.clone(),
+                        conflict: conflict.clone(),
+                    })
+            })
+        })
+        .collect();
+    reports.sort_by(|a, b| (&a.product, &a.version).cmp(&(&b.product, &b.version)));
+    axum::Json(reports)
+}
+
+/// Release cadence of every product in the latest collected data.
+async fn get_cadence(State(state): State<AppState>) -> impl IntoResponse {
+    let data: bundle::Dataset = state
+        .cycles
+        .read()
+        .await
+        .iter()
+        .map(|(product, rows)| (product.clone(), rows.clone()))
+        .collect();
+    match analytics::cadence(&data, chrono::Utc::now().date_naive()) {
+        Ok(cadences) => axum::Json(cadences).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Could not compute cadence: {e}"),
+        )
+            .into_response(),
+    }
+}
+
+/// Longest the request waits for a query. Polars cannot be interrupted, so a
+/// query that times out keeps running in the background until it finishes.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Queries running at once, including timed out ones still finishing.
+const MAX_CONCURRENT_QUERIES: usize = 2;
+const DEFAULT_QUERY_ROWS: usize = 1_000;
+const MAX_QUERY_ROWS: usize = 10_000;
+
+#[derive(Deserialize)]
+struct QueryRequest {
+    sql: String,
+    /// Maximum number of rows, capped at `MAX_QUERY_ROWS`
+    limit: Option<usize>,
+}
+
+/// Runs a read-only SQL query over the latest collected data.
+async fn post_query(
+    State(state): State<AppState>,
+    axum::Json(request): axum::Json<QueryRequest>,
+) -> impl IntoResponse {
+    let Ok(permit) = state.queries.clone().try_acquire_owned() else {
+        return (
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            "Too many queries running, try again later",
+        )
+            .into_response();
+    };
+    let data: bundle::Dataset = state
+        .cycles
+        .read()
+        .await
+        .iter()
+        .map(|(product, rows)| (product.clone(), rows.clone()))
+        .collect();
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_QUERY_ROWS)
+        .min(MAX_QUERY_ROWS);
+
+    let task = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        let tables = query::dataset_tables(&data, chrono::Utc::now().date_naive())?;
+        query::run(tables, &request.sql, Some(limit))
+    });
+    match tokio::time::timeout(QUERY_TIMEOUT, task).await {
+        Ok(Ok(Ok(result))) => axum::Json(result).into_response(),
+        Ok(Ok(Err(e))) => (axum::http::StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        Ok(Err(e)) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Query failed: {e}"),
+        )
+            .into_response(),
+        Err(_) => (
+            axum::http::StatusCode::REQUEST_TIMEOUT,
+            format!(
+                "Query exceeded the {}s timeout; it keeps running until it finishes",
+                QUERY_TIMEOUT.as_secs()
+            ),
+        )
+            .into_response(),
+    }
+}
+
+/// Rows held back by validation, from the database when it is configured.
+async fn get_quarantine(State(state): State<AppState>) -> impl IntoResponse {
+    if let Some(db) = &state.db {
+        return match db.list_quarantine().await {
+            Ok(rows) => axum::Json(rows).into_response(),
+            Err(e) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to load quarantine: {e}"),
+            )
+                .into_response(),
+        };
+    }
+
+    let quarantine = state.quarantine.read().await;
+    let mut rows: Vec<&QuarantinedRow> = quarantine.values().flatten().collect();
+    rows.sort_by(|a, b| a.product.cmp(&b.product));
+    axum::Json(rows).into_response()
+}
+
+/// Accepts the run held back for a degraded source, replacing the target's
+/// data with it.
+async fn acknowledge_degraded(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let Some(run) = state.guard.read().await.degraded.get(&name).cloned() else {
+        return (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No degraded run to acknowledge for {name}"),
+        )
+            .into_response();
+    };
+
+    let (status, message) = if run.rows.iter().any(|r| r.fallback) {
+        (RunStatus::Fallback, Some(FALLBACK_MESSAGE))
+    } else {
+        (RunStatus::Active, None)
+    };
+    if let Some(db) = &state.db {
+        let stored = match db.replace_cycles(&name, &run.rows).await {
+            Ok(()) => db.store_baseline(&name, &run.current).await,
+            Err(e) => Err(e),
+        };
+        let stored = match stored {
+            Ok(()) => db.update_latest_run(&name, status, message).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = stored {
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to store {name} data: {e}"),
+            )
+                .into_response();
+        }
+    }
+
+    state
+        .cycles
+        .write()
+        .await
+        .insert(name.clone(), run.rows.clone());
+    let mut guard = state.guard.write().await;
+    guard.degraded.remove(&name);
+    guard.baselines.insert(name.clone(), run.current);
+    if let Some(latest) = state
+        .runs
+        .write()
+        .await
+        .get_mut(&name)
+        .and_then(|r| r.last_mut())
+    {
+        latest.status = status;
+        latest.error_kind = None;
+        latest.error_message = message.map(str::to_string);
+    }
+    println!("✅ {name}: degraded run acknowledged");
+    axum::Json(run).into_response()
+}
+
+/// Merges the inventory of the configuration and the database.
+async fn load_inventory(state: &AppState) -> Result<Vec<InventoryEntry>, axum::response::Response> {
+    let mut sources = vec![state.config.inventory.clone()];
+    if let Some(db) = &state.db {
+        match db.list_inventory().await {
+            Ok(entries) => sources.push(entries),
+            Err(e) => {
+                return Err((
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to load inventory: {e}"),
+                )
+                    .into_response())
+            }
+        }
+    }
+    Ok(inventory::merge(sources))
+}
+
 async fn serve_index() -> impl IntoResponse {
     match tokio::fs::read_to_string("frontend/dist/index.html").await {
         Ok(content) => axum::response::Html(content),
@@ -148,99 +758,233 @@ async fn serve_logo_png() -> impl IntoResponse {
     }
 }
 
-// Background metrics collection
-async fn collect_metrics_periodically(
-    metrics: Arc<tokio::sync::RwLock<DashboardMetrics>>,
-    config: Arc<Settings>,
-) {
-    let mut interval = interval(Duration::from_secs(300)); // Update every 5 minutes
-
-    loop {
-        interval.tick().await;
-
-        match collect_current_metrics(&config).await {
-            Ok(new_metrics) => {
-                let mut metrics_guard = metrics.write().await;
-                *metrics_guard = new_metrics;
-                println!("📊 Metrics updated successfully");
+/// Stores what collecting a target produced and returns its run.
+pub async fn apply_run(state: &AppState, result: TargetRun) -> CollectorRun {
+    let TargetRun {
+        run,
+        quarantined,
+        accepted,
+        degraded,
+    } = result;
+    let name = &run.target;
+    if let Some(db) = &state.db {
+        if let Err(e) = db.record_run(&run).await {
+            eprintln!("❌ Failed to record {name} run: {e}");
+        }
+        if let Some((rows, summary)) = &accepted {
+            if let Err(e) = db.store_cycles(name, rows).await {
+                eprintln!("❌ Failed to store {name} data: {e}");
             }
-            Err(e) => {
-                eprintln!("❌ Failed to update metrics: {e}");
+            if let Err(e) = db.store_baseline(name, summary).await {
+                eprintln!("❌ Failed to store {name} baseline: {e}");
+            }
+        }
+        if let Some(rows) = &quarantined {
+            if let Err(e) = db.store_quarantine(name, rows).await {
+                eprintln!("❌ Failed to store {name} quarantine: {e}");
             }
         }
     }
+    if let Some(rows) = quarantined {
+        state.quarantine.write().await.insert(name.clone(), rows);
+    }
+    {
+        let mut guard = state.guard.write().await;
+        if let Some((rows, summary)) = accepted {
+            state.cycles.write().await.insert(name.clone(), rows);
+            guard.degraded.remove(name);
+            guard.baselines.insert(name.clone(), summary);
+        }
+        if let Some(degraded) = degraded {
+            guard.degraded.insert(name.clone(), degraded);
+        }
+    }
+    let mut history = state.runs.write().await;
+    let runs = history.entry(name.clone()).or_default();
+    runs.push(run.clone());
+    if runs.len() > MAX_RUN_HISTORY {
+        runs.remove(0);
+    }
+    run
 }
 
-async fn collect_current_metrics(config: &Settings) -> Result<DashboardMetrics> {
-    let start_time = std::time::Instant::now();
-    let mut collector_stats = Vec::new();
-    let mut total_versions = 0;
-    let mut successful_collections = 0;
-    let mut total_collections = 0;
-
-    for target in &config.targets {
-        if !target.enabled {
-            continue;
+/// The distinct sources the rows of a collector were read from, in priority
+/// order; `None` when no row records its provenance.
+fn sources_used(rows: &[ProductCycle]) -> Option<String> {
+    let mut used: Vec<&str> = Vec::new();
+    for sourced in rows
+        .iter()
+        .flat_map(|r| r.provenance.iter().flat_map(|p| &p.sources))
+    {
+        if !used.contains(&sourced.source.as_str()) {
+            used.push(&sourced.source);
         }
+    }
+    (!used.is_empty()).then(|| used.join("+"))
+}
 
-        total_collections += 1;
-        let collector = create_collector(target, config.github_token.as_deref());
-
-        if let Some(collector) = collector {
-            let collector_start_time = std::time::Instant::now();
-            match collector.collect().await {
-                Ok(df) => {
-                    let response_time = collector_start_time.elapsed().as_millis() as f64;
-                    let version_count = df.height();
-                    total_versions += version_count;
-                    successful_collections += 1;
-
-                    let performance_category = match version_count {
-                        0 => "No Data",
-                        1..=10 => "Low Volume",
-                        11..=50 => "Medium Volume",
-                        51..=200 => "High Volume",
-                        _ => "Very High Volume",
-                    };
-
-                    collector_stats.push(CollectorMetric {
-                        name: target.name.clone(),
-                        version_count,
-                        status: "Active".to_string(),
-                        last_collection: chrono::Utc::now().to_rfc3339(),
-                        performance_category: performance_category.to_string(),
-                        response_time,
-                        error_message: None,
-                    });
+/// Collects a target under the shared concurrency limits. A run that
+/// degrades from the target's baseline, or drops EOL dates of its `accepted`
+/// rows, is held back instead of accepted.
+pub async fn collect_target(
+    config: &Settings,
+    limits: &CollectionLimits,
+    baseline: Option<RunSummary>,
+    accepted: &[ProductCycle],
+    target: &Target,
+    collector: &dyn Collector,
+) -> TargetRun {
+    let today = chrono::Utc::now().date_naive();
+    let collection = limits
+        .run(collector, config.collection.timeout(target))
+        .await;
+    let timed_out = matches!(collection.result, Err(CollectError::Timeout(_)));
+    let outcome = collection
+        .result
+        .and_then(|df| validate(&target.name, &df, &config.validation, today));
+    let mut result = TargetRun {
+        run: CollectorRun {
+            target: target.name.clone(),
+            started_at: collection.started_at,
+            duration_ms: collection.duration.as_millis() as u64,
+            status: if timed_out {
+                RunStatus::TimedOut
+            } else {
+                RunStatus::Failed
+            },
+            rows: 0,
+            quarantined: 0,
+            error_kind: None,
+            error_message: None,
+            source: None,
+        },
+        quarantined: None,
+        accepted: None,
+        degraded: None,
+    };
+    let run = &mut result.run;
+
+    match outcome {
+        Ok(validated) => {
+            let mut rows = validated.rows;
+            run.rows = rows.len();
+            run.quarantined = validated.quarantined.len();
+            collectors::report_quarantine(&validated.quarantined);
+            result.quarantined = Some(validated.quarantined);
+            let summary = RunSummary::of(&rows);
+            let mut reasons = match baseline {
+                Some(baseline) if config.regression_guard.enabled => {
+                    summary.degradations(&baseline, config.regression_guard.max_row_drop_percent)
                 }
-                Err(e) => {
-                    let response_time = collector_start_time.elapsed().as_millis() as f64;
-                    collector_stats.push(CollectorMetric {
-                        name: target.name.clone(),
-                        version_count: 0,
-                        status: "Failed".to_string(),
-                        last_collection: chrono::Utc::now().to_rfc3339(),
-                        performance_category: "No Data".to_string(),
-                        response_time,
-                        error_message: Some(format!("{e}")),
+                _ => Vec::new(),
+            };
+            let fallback = rows.iter().any(|r| r.fallback);
+            let fetched = Sourced {
+                source: collector.source().to_string(),
+                fetched_at: collection.started_at,
+            };
+            collectors::infer(target, &mut rows, &fetched);
+            run.source = sources_used(&rows);
+            if baseline.is_some() && config.regression_guard.enabled {
+                let lost = regression::lost_eol_dates(&rows, accepted);
+                if !lost.is_empty() {
+                    reasons.push(format!("EOL dates removed for {}", lost.join(", ")));
+                }
+            }
+            match baseline.filter(|_| !reasons.is_empty()) {
+                None => {
+                    result.accepted = Some((rows, summary));
+                    if fallback {
+                        run.status = RunStatus::Fallback;
+                        run.error_message = Some(FALLBACK_MESSAGE.to_string());
+                    } else {
+                        run.status = RunStatus::Active;
+                    }
+                }
+                Some(accepted) => {
+                    let message = reasons.join("; ");
+                    eprintln!("⚠️  {}: source degraded: {message}", target.name);
+                    result.degraded = Some(DegradedRun {
+                        product: target.name.clone(),
+                        reasons,
+                        accepted,
+                        current: summary,
+                        detected_at: chrono::Utc::now().to_rfc3339(),
+                        rows,
                     });
+                    run.status = RunStatus::Degraded;
+                    run.error_message = Some(message);
                 }
             }
         }
+        Err(e) => {
+            eprintln!("❌ Failed to collect {}: {e}", target.name);
+            run.error_kind = Some(e.kind().to_string());
+            run.error_message = Some(format!("{e}"));
+        }
+    }
+    result
+}
+
+impl From<&CollectorRun> for CollectorMetric {
+    fn from(run: &CollectorRun) -> Self {
+        let performance_category = match (run.status, run.rows) {
+            (RunStatus::Failed, _) | (_, 0) => "No Data",
+            (_, 1..=10) => "Low Volume",
+            (_, 11..=50) => "Medium Volume",
+            (_, 51..=200) => "High Volume",
+            _ => "Very High Volume",
+        };
+        let finished_at = run.started_at + chrono::Duration::milliseconds(run.duration_ms as i64);
+        CollectorMetric {
+            name: run.target.clone(),
+            version_count: run.rows,
+            status: run.status.display().to_string(),
+            last_collection: finished_at.to_rfc3339(),
+            performance_category: performance_category.to_string(),
+            response_time: run.duration_ms as f64,
+            error_message: run.error_message.clone(),
+            source: run.source.clone(),
+            quarantined: run.quarantined,
+            next_run: None,
+        }
     }
+}
 
-    let total_collectors = config.targets.iter().filter(|t| t.enabled).count();
-    let active_collectors = collector_stats
+/// Dashboard metrics from the latest run of each enabled target.
+fn dashboard_metrics(config: &Settings, latest: &[CollectorRun]) -> DashboardMetrics {
+    let enabled: Vec<&str> = config
+        .targets
         .iter()
-        .filter(|c| c.status == "Active")
-        .count();
-    let success_rate = if total_collections > 0 {
-        (successful_collections as f64 / total_collections as f64) * 100.0
+        .filter(|t| t.enabled)
+        .map(|t| t.name.as_str())
+        .collect();
+    let runs: Vec<&CollectorRun> = latest
+        .iter()
+        .filter(|r| enabled.contains(&r.target.as_str()))
+        .collect();
+    let collector_stats: Vec<CollectorMetric> =
+        runs.iter().map(|&run| CollectorMetric::from(run)).collect();
+
+    let total_collectors = enabled.len();
+    let active_collectors = runs.iter().filter(|r| r.status.succeeded()).count();
+    let success_rate = if total_collectors > 0 {
+        (active_collectors as f64 / total_collectors as f64) * 100.0
     } else {
         0.0
     };
 
-    let average_response_time = start_time.elapsed().as_millis() as f64 / total_collections as f64;
+    // A timed-out run's duration is its timeout rather than a latency.
+    let completed: Vec<&CollectorRun> = runs
+        .iter()
+        .copied()
+        .filter(|r| r.status != RunStatus::TimedOut)
+        .collect();
+    let average_response_time = if !completed.is_empty() {
+        completed.iter().map(|r| r.duration_ms as f64).sum::<f64>() / completed.len() as f64
+    } else {
+        0.0
+    };
 
     let version_counts: Vec<usize> = collector_stats.iter().map(|c| c.version_count).collect();
     let avg_versions = if !version_counts.is_empty() {
@@ -269,50 +1013,19 @@ async fn collect_current_metrics(config: &Settings) -> Result<DashboardMetrics>
         average_response_time,
     };
 
-    Ok(DashboardMetrics {
+    DashboardMetrics {
         total_collectors,
         active_collectors,
         failed_collectors: total_collectors - active_collectors,
-        total_versions,
-        last_updated: chrono::Utc::now().to_rfc3339(),
+        timed_out_collectors: runs.len() - completed.len(),
+        total_versions: runs.iter().map(|r| r.rows).sum(),
+        quarantined_rows: runs.iter().map(|r| r.quarantined).sum(),
+        last_updated: collector_stats
+            .iter()
+            .map(|c| c.last_collection.clone())
+            .max()
+            .unwrap_or_default(),
         collector_stats,
         system_health,
-    })
-}
-
-fn create_collector(
-    target: &versionwatch_config::Target,
-    github_token: Option<&str>,
-) -> Option<Box<dyn Collector + Send + Sync>> {
-    match target.name.as_str() {
-        "apache" => Some(Box::new(ApacheCollector::new())),
-        "docker" => Some(Box::new(DockerCollector::new(&target.name))),
-        "eclipse-temurin" => Some(Box::new(EclipseTemurinCollector::new(&target.name))),
-        "go" => Some(Box::new(GoCollector::new(&target.name))),
-        "mongodb" => Some(Box::new(MongoDbCollector::new(&target.name))),
-        "mysql" => {
-            if let Some(token) = github_token {
-                Some(Box::new(MySqlCollector::with_token(
-                    &target.name,
-                    token.to_string(),
-                )))
-            } else {
-                Some(Box::new(MySqlCollector::new(&target.name)))
-            }
-        }
-        "node" => Some(Box::new(NodeCollector::new(&target.name))),
-        "perl" => Some(Box::new(PerlCollector)),
-        "php" => Some(Box::new(PhpCollector::new(&target.name))),
-        "postgresql" => Some(Box::new(PostgresqlCollector::new(&target.name))),
-        "swift" => Some(Box::new(SwiftCollector::new(&target.name))),
-        "kong" => Some(Box::new(KongCollector::new(&target.name))),
-        "caddy" => Some(Box::new(CaddyCollector::new(&target.name))),
-        "kotlin" => Some(Box::new(KotlinCollector::new(&target.name))),
-        "nginx" => Some(Box::new(NginxCollector::new(&target.name))),
-        "python" => Some(Box::new(PythonCollector::new(&target.name))),
-        "ruby" => Some(Box::new(RubyCollector::new(&target.name))),
-        "rust" => Some(Box::new(RustCollector::new(&target.name))),
-        "scala" => Some(Box::new(ScalaCollector::new(&target.name))),
-        _ => None,
     }
 }
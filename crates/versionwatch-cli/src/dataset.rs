@@ -0,0 +1,24 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use versionwatch_collect::bundle::{self, BundleFormat};
+
+use crate::dashboard::CycleStore;
+
+/// Loads lifecycle data from a bundle written by `export` or `check --save-dataset`,
+/// so commands can run without network access.
+pub fn load(dir: &Path) -> Result<CycleStore> {
+    let (_, data) = bundle::read_bundle(dir)
+        .with_context(|| format!("Could not read bundle {}", dir.display()))?;
+    Ok(data.into_iter().collect())
+}
+
+/// Saves collected lifecycle data as a Parquet bundle, the same format `export` writes.
+pub fn save(dir: &Path, cycles: &CycleStore) -> Result<()> {
+    let data: bundle::Dataset = cycles
+        .iter()
+        .map(|(product, rows)| (product.clone(), rows.clone()))
+        .collect();
+    bundle::write_bundle(dir, BundleFormat::Parquet, &data, &[], "check")
+        .with_context(|| format!("Could not write bundle {}", dir.display()))?;
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use versionwatch_core::domain::inventory::{evaluate, InventoryEntry, InventoryStatus};
+use versionwatch_core::domain::lifecycle::Lifecycle;
+
+use crate::dashboard::CycleStore;
+
+/// Inventory files are either a bare list of entries or an `inventory:` document,
+/// so the same block can be copied from `config.yaml`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InventoryFile {
+    List(Vec<InventoryEntry>),
+    Document { inventory: Vec<InventoryEntry> },
+}
+
+/// Loads inventory entries from a YAML or CSV file, picked by extension.
+pub fn load_file(path: &Path) -> Result<Vec<InventoryEntry>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read inventory file {}", path.display()))?;
+
+    let entries = match path.extension().and_then(|e| e.to_str()) {
+        Some("csv") => csv::Reader::from_reader(contents.as_bytes())
+            .deserialize()
+            .collect::<Result<Vec<InventoryEntry>, _>>()
+            .with_context(|| format!("Could not parse inventory file {}", path.display()))?,
+        _ => match serde_yaml::from_str(&contents)
+            .with_context(|| format!("Could not parse inventory file {}", path.display()))?
+        {
+            InventoryFile::List(entries) => entries,
+            InventoryFile::Document { inventory } => inventory,
+        },
+    };
+
+    Ok(entries)
+}
+
+/// Merges inventory sources; later sources win for the same service, environment and product.
+pub fn merge(sources: impl IntoIterator<Item = Vec<InventoryEntry>>) -> Vec<InventoryEntry> {
+    let mut merged: Vec<InventoryEntry> = Vec::new();
+    for entry in sources.into_iter().flatten() {
+        match merged.iter_mut().find(|e| {
+            e.service == entry.service
+                && e.environment == entry.environment
+                && e.product == entry.product
+        }) {
+            Some(existing) => *existing = entry,
+            None => merged.push(entry),
+        }
+    }
+    merged
+}
+
+/// Evaluates every entry against the collected cycles of its product.
+pub fn evaluate_all(
+    entries: &[InventoryEntry],
+    cycles: &CycleStore,
+    today: chrono::NaiveDate,
+) -> Vec<InventoryStatus> {
+    let mut lifecycles: HashMap<&str, Lifecycle> = HashMap::new();
+    entries
+        .iter()
+        .map(|entry| {
+            let product = entry.product.as_str();
+            if !lifecycles.contains_key(product) {
+                if let Some(rows) = cycles.get(product) {
+                    lifecycles.insert(product, Lifecycle::new(product, rows));
+                }
+            }
+            evaluate(entry, lifecycles.get(product), today)
+        })
+        .collect()
+}
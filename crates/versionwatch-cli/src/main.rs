@@ -1,13 +1,36 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::{Parser, Subcommand};
-use std::path::Path;
+use output::{BundleFormat, Format};
+use std::path::{Path, PathBuf};
+use versionwatch_collect::bundle;
+use versionwatch_collect::{analytics, query};
+use versionwatch_core::domain::collector_run::CollectorRun;
+use versionwatch_core::domain::inventory::InventoryEntry;
+use versionwatch_db::Db;
+use versionwatch_scan::check::PolicyContext;
+use versionwatch_scan::sbom::Sbom;
+use versionwatch_scan::{report, workflows};
 
+mod audit;
+mod badge;
+mod check;
+mod collectors;
+mod cycles;
 mod dashboard;
+mod dataset;
+mod inventory;
+mod output;
+mod policy;
+mod scan;
+mod scheduler;
 
 #[derive(Parser)]
 #[command(name = "versionwatch")]
 #[command(about = "VersionWatch CLI - Monitor software versions")]
 struct Cli {
+    /// Output format of command results
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    format: Format,
     #[command(subcommand)]
     command: Commands,
 }
@@ -23,20 +46,602 @@ enum Commands {
         #[arg(long, default_value = "8080")]
         port: u16,
     },
+    /// Collect targets now, as the dashboard does, storing the results and
+    /// printing each one as it completes
+    ///
+    /// Exits 1 when a collection fails or is held back.
+    Run {
+        /// Target names, e.g. "node"
+        #[arg(required = true)]
+        targets: Vec<String>,
+    },
+    /// Show the release cycles of a product and their support status
+    Cycles {
+        /// Product (target) name, e.g. "node"
+        product: String,
+        /// List individual releases as collected instead of cycles
+        #[arg(long)]
+        releases: bool,
+        /// Only show LTS cycles or releases
+        #[arg(long)]
+        lts: bool,
+        /// Use a bundle written by `export` instead of collecting lifecycle data
+        #[arg(long, value_name = "DIR")]
+        dataset: Option<PathBuf>,
+    },
+    /// Export collected data as products, cycles and releases files with a manifest
+    ///
+    /// Collection history is exported from the database as a history table.
+    Export {
+        /// Directory to write the bundle to
+        #[arg(long)]
+        out: PathBuf,
+        /// File format of the bundle's tables. The global `--format` only
+        /// applies to printed results and is not accepted here.
+        #[arg(long, value_enum, default_value_t = BundleFormat::Parquet)]
+        bundle_format: BundleFormat,
+        /// Re-export a bundle written by `export` instead of the database or
+        /// freshly collected data
+        #[arg(long, value_name = "DIR")]
+        dataset: Option<PathBuf>,
+    },
+    /// Load a bundle written by `export` into the database
+    Import {
+        /// Bundle directory
+        dir: PathBuf,
+    },
+    /// Run a read-only SQL query over the products, cycles and releases tables
+    Query {
+        /// A single SELECT statement
+        sql: String,
+        /// Query a bundle written by `export` instead of the database
+        #[arg(long, value_name = "DIR", alias = "dataset")]
+        bundle: Option<PathBuf>,
+        /// Maximum number of rows to print
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+    },
+    /// Show how often each product ships: gaps between releases, patches per
+    /// cycle, cycle lifespans and time since the last release
+    Cadence {
+        /// Only show these products
+        products: Vec<String>,
+        /// Use a bundle written by `export` instead of the database or freshly
+        /// collected data
+        #[arg(long, value_name = "DIR")]
+        dataset: Option<PathBuf>,
+    },
+    /// Track deployed product versions
+    Inventory {
+        #[command(subcommand)]
+        command: InventoryCommands,
+    },
+    /// Scan files for pinned product versions and report their lifecycle status
+    Scan {
+        #[command(subcommand)]
+        command: ScanCommands,
+    },
+    /// Scan a repository and exit 0, 1 or 2 when it passes, warns or fails
+    Check {
+        /// Files or directories to scan
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+        /// Report cycles reaching EOL within this many days as near EOL
+        #[arg(long, default_value = "90")]
+        near_eol_days: i64,
+        /// Write results as a SARIF 2.1.0 log, for code scanning
+        #[arg(long, value_name = "FILE")]
+        sarif: Option<PathBuf>,
+        /// Write results as a JUnit XML report, for CI test tabs
+        #[arg(long, value_name = "FILE")]
+        junit: Option<PathBuf>,
+        /// Use a bundle written by `export` or --save-dataset instead of collecting
+        /// lifecycle data
+        #[arg(long, value_name = "DIR")]
+        dataset: Option<PathBuf>,
+        /// Save the lifecycle data used by the check as a bundle, for later offline runs
+        #[arg(long, value_name = "DIR", conflicts_with = "dataset")]
+        save_dataset: Option<PathBuf>,
+        /// Lifecycle policy to apply instead of the built-in one; defaults to `policy` in config.yaml
+        #[arg(long, value_name = "FILE")]
+        policy: Option<PathBuf>,
+        /// Environment the repository deploys to, for rules scoped by environment
+        #[arg(long)]
+        environment: Option<String>,
+        /// Tag of the repository, for rules scoped by tag
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Evaluate the inventory against the lifecycle policy and exit 0, 1 or 2
+    Policy {
+        /// Policy file; defaults to `policy` in config.yaml
+        #[arg(long, value_name = "FILE")]
+        policy: Option<PathBuf>,
+        /// Additional inventory file (YAML or CSV)
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Use a bundle written by `export` instead of collecting lifecycle data
+        #[arg(long, value_name = "DIR")]
+        dataset: Option<PathBuf>,
+    },
+    /// Report how far locked library dependencies are behind their registries
+    Audit {
+        /// Lockfiles, or directories to search for them
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+    },
+    /// Check SBOM components against tracked products
+    Sbom {
+        #[command(subcommand)]
+        command: SbomCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum InventoryCommands {
+    /// Evaluate the inventory against freshly collected data
+    Status {
+        /// Additional inventory file (YAML or CSV)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Store an inventory file (YAML or CSV) in the database
+    Import {
+        /// Inventory file to import
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum ScanCommands {
+    /// Scan Dockerfiles for base image versions
+    Dockerfile {
+        /// Dockerfiles, or directories to search for them
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Override an ARG default, like `docker build --build-arg`
+        #[arg(long = "build-arg", value_name = "NAME=VALUE")]
+        build_args: Vec<String>,
+        /// Report cycles reaching EOL within this many days as near EOL
+        #[arg(long, default_value = "90")]
+        near_eol_days: i64,
+    },
+    /// Scan docker-compose files, Kubernetes manifests and Helm values for images
+    Manifests {
+        /// Manifest files, or directories to search for them
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Report cycles reaching EOL within this many days as near EOL
+        #[arg(long, default_value = "90")]
+        near_eol_days: i64,
+    },
+    /// Scan runtime pin files (.nvmrc, go.mod, .tool-versions, mise.toml, ...) for runtime versions
+    Runtimes {
+        /// Pin files, or directories to search for them
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Report cycles reaching EOL within this many days as near EOL
+        #[arg(long, default_value = "90")]
+        near_eol_days: i64,
+    },
+    /// Scan GitHub Actions workflows for runner images and setup action versions
+    Workflows {
+        /// Workflow files, or directories to search for them
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Report cycles reaching EOL within this many days as near EOL
+        #[arg(long, default_value = "90")]
+        near_eol_days: i64,
+        /// Print problems as GitHub Actions annotations instead of a table
+        #[arg(long)]
+        annotations: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SbomCommands {
+    /// Report the lifecycle status of components in a CycloneDX or SPDX SBOM
+    Report {
+        /// SBOM file (CycloneDX JSON/XML or SPDX JSON)
+        file: PathBuf,
+        /// Report cycles reaching EOL within this many days as near EOL
+        #[arg(long, default_value = "90")]
+        near_eol_days: i64,
+    },
+    /// Write the SBOM back with lifecycle properties added to matched components
+    Enrich {
+        /// SBOM file (CycloneDX JSON/XML or SPDX JSON)
+        file: PathBuf,
+        /// Where to write the enriched SBOM; defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Report cycles reaching EOL within this many days as near EOL
+        #[arg(long, default_value = "90")]
+        near_eol_days: i64,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let config = versionwatch_config::load(Path::new("config.yaml"))?;
+    let format = cli.format;
 
     match cli.command {
         Commands::Serve { host, port } => {
-            let config = versionwatch_config::load(Path::new("config.yaml"))?;
+            let db = connect_db().await?;
 
             println!("🚀 Starting VersionWatch dashboard (React) on http://{host}:{port}");
-            dashboard::start_server(&host, port, &config).await?;
+            dashboard::start_server(&host, port, &config, db).await?;
+        }
+        Commands::Run { targets } => {
+            let mut due = Vec::new();
+            for name in &targets {
+                let target = collectors::target_for(&config, name);
+                let collector =
+                    collectors::create_collector(&target, config.github_token.as_deref())
+                        .with_context(|| format!("No collector implements {name}"))?;
+                due.push((target, collector));
+            }
+            let state = dashboard::AppState::new(&config, connect_db().await?).await?;
+            let (mut completed, _) = scheduler::run_targets(&state, due);
+            let mut runs = Vec::new();
+            while let Some(run) = completed.recv().await {
+                match format {
+                    Format::Ndjson => println!("{}", serde_json::to_string(&run)?),
+                    _ => eprintln!(
+                        "{} {}: {} in {} ms",
+                        if run.status.succeeded() { "✅" } else { "❌" },
+                        run.target,
+                        run.status.display(),
+                        run.duration_ms
+                    ),
+                }
+                runs.push(run);
+            }
+            if format != Format::Ndjson {
+                output::render(format, &runs)?;
+            }
+            if runs.iter().any(|r| !r.status.succeeded()) {
+                std::process::exit(1);
+            }
         }
+        Commands::Cycles {
+            product,
+            releases,
+            lts,
+            dataset,
+        } => {
+            let mut cycles = match dataset {
+                Some(file) => dataset::load(&file)?,
+                None => collectors::collect_products(&config, vec![product.as_str()]).await,
+            };
+            let rows = cycles
+                .remove(&product)
+                .with_context(|| format!("No data collected for {product}"))?;
+            if releases {
+                let rows: Vec<_> = rows.into_iter().filter(|r| r.lts || !lts).collect();
+                output::render(format, &rows)?;
+            } else {
+                let today = chrono::Utc::now().date_naive();
+                let mut reports = cycles::reports(&product, &rows, today);
+                reports.retain(|r| r.cycle.lts || !lts);
+                output::render(format, &reports)?;
+            }
+        }
+        Commands::Export {
+            out,
+            bundle_format,
+            dataset,
+        } => {
+            if format != Format::Table {
+                bail!("export writes files, choose their format with --bundle-format");
+            }
+            let (source, data) = match dataset {
+                Some(file) => ("dataset", dataset::load(&file)?.into_iter().collect()),
+                None => match stored_cycles().await? {
+                    Some(data) => ("database", data),
+                    None => ("collectors", collect_enabled(&config).await),
+                },
+            };
+            let history = match source {
+                "database" => stored_history().await?,
+                _ => Vec::new(),
+            };
+            let manifest =
+                bundle::write_bundle(&out, bundle_format.into(), &data, &history, source)?;
+            for table in &manifest.tables {
+                println!(
+                    "📦 {} rows → {}",
+                    table.rows,
+                    out.join(&table.file).display()
+                );
+            }
+        }
+        Commands::Import { dir } => {
+            let db = connect_db()
+                .await?
+                .context("DATABASE_URL must be set to import a bundle")?;
+            let (manifest, data) = bundle::read_bundle(&dir)
+                .with_context(|| format!("Could not read bundle {}", dir.display()))?;
+            for (product, rows) in &data {
+                db.replace_cycles(product, rows).await?;
+            }
+            let history = bundle::read_history(&dir)?;
+            let mut new_runs = 0;
+            for run in &history {
+                if db.record_run(run).await? {
+                    new_runs += 1;
+                }
+            }
+            println!(
+                "📦 Imported {} rows for {} products and {} new collection runs from a {} bundle generated at {}",
+                data.values().map(Vec::len).sum::<usize>(),
+                data.len(),
+                new_runs,
+                manifest.format.extension(),
+                manifest.generated_at
+            );
+        }
+        Commands::Query { sql, bundle, limit } => {
+            let tables = match bundle {
+                Some(dir) => query::bundle_tables(&dir)
+                    .with_context(|| format!("Could not read bundle {}", dir.display()))?,
+                None => {
+                    let data = match stored_cycles().await? {
+                        Some(data) => data,
+                        None => collect_enabled(&config).await,
+                    };
+                    query::dataset_tables(&data, chrono::Utc::now().date_naive())?
+                }
+            };
+            output::render_query(format, &query::run(tables, &sql, Some(limit))?)?;
+        }
+        Commands::Cadence { products, dataset } => {
+            let mut data: bundle::Dataset = match dataset {
+                Some(file) => dataset::load(&file)?.into_iter().collect(),
+                None => match stored_cycles().await? {
+                    Some(data) => data,
+                    None => collect_enabled(&config).await,
+                },
+            };
+            if !products.is_empty() {
+                data.retain(|product, _| products.contains(product));
+            }
+            let today = chrono::Utc::now().date_naive();
+            output::render(format, &analytics::cadence(&data, today)?)?;
+        }
+        Commands::Inventory { command } => match command {
+            InventoryCommands::Status { file } => {
+                let entries = load_inventory(&config, file.as_deref()).await?;
+                let cycles =
+                    collectors::collect_products(&config, inventory_products(&entries)).await;
+
+                let today = chrono::Utc::now().date_naive();
+                let statuses = inventory::evaluate_all(&entries, &cycles, today);
+                output::render(format, &statuses)?;
+            }
+            InventoryCommands::Import { file } => {
+                let db = connect_db()
+                    .await?
+                    .context("DATABASE_URL must be set to import an inventory")?;
+                let entries = inventory::load_file(&file)?;
+                for entry in &entries {
+                    db.upsert_inventory_entry(entry).await?;
+                }
+                println!("📦 Imported {} inventory entries", entries.len());
+            }
+        },
+        Commands::Scan { command } => match command {
+            ScanCommands::Dockerfile {
+                paths,
+                build_args,
+                near_eol_days,
+            } => {
+                let findings = scan::dockerfiles(&paths, &build_args)?;
+                let reports = scan::evaluate(&config, findings, near_eol_days).await;
+                output::render(format, &reports)?;
+            }
+            ScanCommands::Manifests {
+                paths,
+                near_eol_days,
+            } => {
+                let findings = scan::manifests(&paths)?;
+                let reports = scan::evaluate(&config, findings, near_eol_days).await;
+                output::render(format, &reports)?;
+            }
+            ScanCommands::Runtimes {
+                paths,
+                near_eol_days,
+            } => {
+                let findings = scan::runtimes(&paths)?;
+                let reports = scan::evaluate(&config, findings, near_eol_days).await;
+                output::render(format, &reports)?;
+            }
+            ScanCommands::Workflows {
+                paths,
+                near_eol_days,
+                annotations,
+            } => {
+                let findings = scan::workflows(&paths)?;
+                let mut reports = scan::evaluate(&config, findings, near_eol_days).await;
+                let today = chrono::Utc::now().date_naive();
+                workflows::evaluate_runners(&mut reports, today, near_eol_days);
+                if annotations {
+                    scan::print_annotations(&reports);
+                } else {
+                    output::render(format, &reports)?;
+                }
+            }
+        },
+        Commands::Check {
+            paths,
+            near_eol_days,
+            sarif,
+            junit,
+            dataset,
+            save_dataset,
+            policy,
+            environment,
+            tags,
+        } => {
+            let policy = policy::load(&config, policy.as_deref())?;
+            let findings = check::scan_all(&paths)?;
+            let cycles = match dataset {
+                Some(file) => dataset::load(&file)?,
+                None => collectors::collect_products(&config, scan::products(&findings)).await,
+            };
+            if let Some(file) = save_dataset {
+                dataset::save(&file, &cycles)?;
+            }
+
+            let today = chrono::Utc::now().date_naive();
+            let mut reports = report::evaluate(findings, &cycles, today, near_eol_days);
+            workflows::evaluate_runners(&mut reports, today, near_eol_days);
+            let results = match &policy {
+                Some(policy) => {
+                    policy::warn_expired(policy, today);
+                    let context = PolicyContext { environment, tags };
+                    versionwatch_scan::check::evaluate_policy(
+                        reports, policy, &cycles, &context, today,
+                    )
+                }
+                None => versionwatch_scan::check::evaluate(reports),
+            };
+
+            match format {
+                Format::Table => check::print_summary(&results),
+                format => output::render(format, &results)?,
+            }
+            if let Some(file) = sarif {
+                check::write_sarif(&file, &results)?;
+            }
+            if let Some(file) = junit {
+                check::write_junit(&file, &results)?;
+            }
+            std::process::exit(versionwatch_scan::check::overall(&results).exit_code());
+        }
+        Commands::Policy {
+            policy,
+            file,
+            dataset,
+        } => {
+            let policy = policy::load(&config, policy.as_deref())?
+                .context("No policy given: pass --policy or set `policy` in config.yaml")?;
+            let entries = load_inventory(&config, file.as_deref()).await?;
+            let cycles = match dataset {
+                Some(file) => dataset::load(&file)?,
+                None => collectors::collect_products(&config, inventory_products(&entries)).await,
+            };
+
+            let today = chrono::Utc::now().date_naive();
+            policy::warn_expired(&policy, today);
+            let violations = policy::evaluate_inventory(&policy, &entries, &cycles, today);
+            output::render(format, &violations)?;
+            if format == Format::Table {
+                policy::print_summary(&violations);
+            }
+            std::process::exit(policy::overall(&violations).exit_code());
+        }
+        Commands::Audit { paths } => {
+            let dependencies = audit::lockfiles(&paths)?;
+            let entries = audit::evaluate(dependencies).await;
+            output::render(format, &entries)?;
+            if format == Format::Table {
+                audit::print_summary(&entries);
+            }
+        }
+        Commands::Sbom { command } => match command {
+            SbomCommands::Report {
+                file,
+                near_eol_days,
+            } => {
+                let sbom = Sbom::load(&file)?;
+                let findings = sbom.findings();
+                let reports = scan::evaluate(&config, findings, near_eol_days).await;
+                output::render(format, &reports)?;
+            }
+            SbomCommands::Enrich {
+                file,
+                output,
+                near_eol_days,
+            } => {
+                let sbom = Sbom::load(&file)?;
+                let reports = scan::evaluate(&config, sbom.findings(), near_eol_days).await;
+                let today = chrono::Utc::now().date_naive();
+                let enriched = sbom.enrich(&reports, today)?;
+                match output {
+                    Some(output) => std::fs::write(&output, enriched)
+                        .with_context(|| format!("Failed to write {}", output.display()))?,
+                    None => println!("{enriched}"),
+                }
+            }
+        },
     }
 
     Ok(())
 }
+
+/// Merges the inventory of `config.yaml`, the database and an optional file.
+async fn load_inventory(
+    config: &versionwatch_config::Settings,
+    file: Option<&Path>,
+) -> Result<Vec<InventoryEntry>> {
+    let mut sources = vec![config.inventory.clone()];
+    if let Some(db) = connect_db().await? {
+        sources.push(db.list_inventory().await?);
+    }
+    if let Some(file) = file {
+        sources.push(inventory::load_file(file)?);
+    }
+    Ok(inventory::merge(sources))
+}
+
+fn inventory_products(entries: &[InventoryEntry]) -> Vec<&str> {
+    let mut products: Vec<&str> = entries.iter().map(|e| e.product.as_str()).collect();
+    products.sort_unstable();
+    products.dedup();
+    products
+}
+
+/// Collects every enabled target on the spot.
+async fn collect_enabled(config: &versionwatch_config::Settings) -> bundle::Dataset {
+    let enabled = config.targets.iter().filter(|t| t.enabled);
+    collectors::collect_products(config, enabled.map(|t| t.name.as_str()))
+        .await
+        .into_iter()
+        .collect()
+}
+
+/// Collected rows stored in the database, when it is configured and not empty.
+async fn stored_cycles() -> Result<Option<bundle::Dataset>> {
+    let Some(db) = connect_db().await? else {
+        return Ok(None);
+    };
+    let mut data = bundle::Dataset::new();
+    for (product, row) in db.list_cycles().await? {
+        data.entry(product).or_default().push(row);
+    }
+    Ok(Some(data).filter(|data| !data.is_empty()))
+}
+
+/// Collection runs of every target stored in the database, oldest first.
+async fn stored_history() -> Result<Vec<CollectorRun>> {
+    let Some(db) = connect_db().await? else {
+        return Ok(Vec::new());
+    };
+    let mut runs = Vec::new();
+    for latest in db.latest_runs().await? {
+        runs.extend(db.list_runs(&latest.target, i64::MAX).await?);
+    }
+    runs.sort_by_key(|r| r.started_at);
+    Ok(runs)
+}
+
+/// Connects to the database when `DATABASE_URL` is set; the database is optional.
+async fn connect_db() -> Result<Option<Db>> {
+    match std::env::var("DATABASE_URL") {
+        Ok(url) => Ok(Some(Db::connect(&url).await?)),
+        Err(_) => Ok(None),
+    }
+}
@@ -0,0 +1,557 @@
+use anyhow::Result;
+use serde::ser::SerializeMap;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{IsTerminal, Write};
+use versionwatch_collect::analytics::Cadence;
+use versionwatch_collect::bundle;
+use versionwatch_collect::query::QueryResult;
+use versionwatch_core::domain::collector_run::{CollectorRun, RunStatus};
+use versionwatch_core::domain::inventory::{DriftStatus, InventoryStatus};
+use versionwatch_core::domain::lifecycle::SupportStatus;
+use versionwatch_core::domain::policy::{Severity, Violation};
+use versionwatch_core::domain::product_cycle::{EolSource, ProductCycle};
+use versionwatch_scan::check::CheckResult;
+use versionwatch_scan::report::{FindingReport, ScanStatus};
+
+use crate::audit::AuditEntry;
+use crate::cycles::CycleReport;
+
+/// How command results are printed.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Aligned columns, colored when printing to a terminal
+    #[default]
+    Table,
+    /// A JSON array of objects
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    Yaml,
+    Csv,
+    /// A GitHub-flavored Markdown table, for PR comments and wikis
+    Markdown,
+}
+
+/// File format of the tables of an exported bundle.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BundleFormat {
+    /// Typed columnar files
+    #[default]
+    Parquet,
+    Csv,
+    Json,
+}
+
+impl From<BundleFormat> for bundle::BundleFormat {
+    fn from(format: BundleFormat) -> Self {
+        match format {
+            BundleFormat::Parquet => bundle::BundleFormat::Parquet,
+            BundleFormat::Csv => bundle::BundleFormat::Csv,
+            BundleFormat::Json => bundle::BundleFormat::Json,
+        }
+    }
+}
+
+/// How a row reads at a glance, used to color terminal tables.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    Neutral,
+    Good,
+    Warning,
+    Bad,
+}
+
+/// A result type printable as a table row.
+///
+/// Tables, CSV and Markdown show the cells; JSON, NDJSON and YAML serialize
+/// the value itself, so their schema is the type's serde representation.
+pub trait Tabular: Serialize {
+    fn headers() -> &'static [&'static str];
+    fn cells(&self) -> Vec<String>;
+    fn tone(&self) -> Tone {
+        Tone::Neutral
+    }
+}
+
+/// Prints items in the given format.
+pub fn render<T: Tabular>(format: Format, items: &[T]) -> Result<()> {
+    let headers: Vec<String> = T::headers().iter().map(|h| h.to_string()).collect();
+    let rows: Vec<(Vec<String>, Tone)> = items.iter().map(|i| (i.cells(), i.tone())).collect();
+    write(format, items, &headers, &rows)
+}
+
+/// Prints the rows of a SQL query, keeping the column order in every format.
+pub fn render_query(format: Format, result: &QueryResult) -> Result<()> {
+    let records: Vec<Record> = result
+        .rows
+        .iter()
+        .map(|values| Record {
+            columns: &result.columns,
+            values,
+        })
+        .collect();
+    let rows: Vec<(Vec<String>, Tone)> = result
+        .rows
+        .iter()
+        .map(|values| {
+            let cells = values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => "-".to_string(),
+                    Value::String(s) => s.clone(),
+                    value => value.to_string(),
+                })
+                .collect();
+            (cells, Tone::Neutral)
+        })
+        .collect();
+    write(format, &records, &result.columns, &rows)?;
+    if result.truncated {
+        eprintln!("✂️  Only the first {} rows are shown", result.rows.len());
+    }
+    Ok(())
+}
+
+/// A query row, serialized as an object whose keys follow the column order.
+struct Record<'a> {
+    columns: &'a [String],
+    values: &'a [Value],
+}
+
+impl Serialize for Record<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for (column, value) in self.columns.iter().zip(self.values) {
+            map.serialize_entry(column, value)?;
+        }
+        map.end()
+    }
+}
+
+fn write<T: Serialize>(
+    format: Format,
+    items: &[T],
+    headers: &[String],
+    rows: &[(Vec<String>, Tone)],
+) -> Result<()> {
+    let mut out = std::io::stdout().lock();
+    match format {
+        Format::Table => table(&mut out, headers, rows, colored())?,
+        Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(items)?)?,
+        Format::Ndjson => {
+            for item in items {
+                writeln!(out, "{}", serde_json::to_string(item)?)?;
+            }
+        }
+        Format::Yaml => write!(out, "{}", serde_yaml::to_string(items)?)?,
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            writer.write_record(headers)?;
+            for (cells, _) in rows {
+                writer.write_record(cells)?;
+            }
+            writer.flush()?;
+        }
+        Format::Markdown => markdown(&mut out, headers, rows)?,
+    }
+    Ok(())
+}
+
+/// Colors are used on terminals unless `NO_COLOR` is set.
+fn colored() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+fn table(
+    out: &mut impl Write,
+    headers: &[String],
+    rows: &[(Vec<String>, Tone)],
+    colored: bool,
+) -> Result<()> {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for (cells, _) in rows {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    match colored {
+        true => writeln!(out, "\x1b[1m{}\x1b[0m", line(headers))?,
+        false => writeln!(out, "{}", line(headers))?,
+    }
+    for (cells, tone) in rows {
+        let color = match tone {
+            Tone::Neutral => None,
+            Tone::Good => Some("32"),
+            Tone::Warning => Some("33"),
+            Tone::Bad => Some("31"),
+        };
+        match color.filter(|_| colored) {
+            Some(color) => writeln!(out, "\x1b[{color}m{}\x1b[0m", line(cells))?,
+            None => writeln!(out, "{}", line(cells))?,
+        }
+    }
+    Ok(())
+}
+
+fn markdown(out: &mut impl Write, headers: &[String], rows: &[(Vec<String>, Tone)]) -> Result<()> {
+    let escape = |cell: &String| cell.replace('|', "\\|").replace('\n', " ");
+    writeln!(out, "| {} |", headers.join(" | "))?;
+    writeln!(out, "|{}", "---|".repeat(headers.len()))?;
+    for (cells, _) in rows {
+        let cells: Vec<String> = cells.iter().map(escape).collect();
+        writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+    Ok(())
+}
+
+fn optional(value: Option<impl ToString>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+/// An EOL date, flagged when it was inferred from a support policy.
+fn eol(date: Option<chrono::NaiveDate>, source: EolSource) -> String {
+    match (date, source) {
+        (Some(date), EolSource::Inferred) => format!("{date} (inferred)"),
+        (date, _) => optional(date),
+    }
+}
+
+impl Tabular for ProductCycle {
+    fn headers() -> &'static [&'static str] {
+        &["NAME", "RELEASED", "EOL", "LTS", "SOURCES"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let sources = self.provenance.as_ref().map(|p| {
+            let names: Vec<&str> = p.sources.iter().map(|s| s.source.as_str()).collect();
+            if self.fallback {
+                format!("{} (fallback)", names.join(", "))
+            } else {
+                names.join(", ")
+            }
+        });
+        vec![
+            self.name.clone(),
+            optional(self.release_date),
+            eol(self.eol_date, self.eol_source),
+            if self.lts { "yes" } else { "no" }.to_string(),
+            optional(sources),
+        ]
+    }
+
+    fn tone(&self) -> Tone {
+        match &self.provenance {
+            Some(p) if !p.conflicts.is_empty() => Tone::Warning,
+            _ if self.fallback => Tone::Warning,
+            _ => Tone::Neutral,
+        }
+    }
+}
+
+impl Tabular for CycleReport {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "PRODUCT", "CYCLE", "LATEST", "RELEASED", "EOL", "LTS", "STATUS",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.product.clone(),
+            self.cycle.name.clone(),
+            self.cycle.latest.clone(),
+            optional(self.cycle.release_date),
+            eol(self.cycle.eol_date, self.cycle.eol_source),
+            if self.cycle.lts { "yes" } else { "no" }.to_string(),
+            self.status.label().to_string(),
+        ]
+    }
+
+    fn tone(&self) -> Tone {
+        match self.status {
+            SupportStatus::Supported => Tone::Good,
+            SupportStatus::SecurityOnly => Tone::Warning,
+            SupportStatus::Eol => Tone::Bad,
+            SupportStatus::Unknown => Tone::Neutral,
+        }
+    }
+}
+
+/// Releases older than this mark a product as quiet.
+const QUIET_DAYS: i64 = 365;
+
+impl Tabular for Cadence {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "PRODUCT",
+            "RELEASES",
+            "CYCLES",
+            "MEAN GAP",
+            "MEDIAN GAP",
+            "PATCHES/CYCLE",
+            "LIFESPAN",
+            "LAST RELEASE",
+            "DAYS SINCE",
+            "PER YEAR",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let decimal = |value: Option<f64>| optional(value.map(|v| format!("{v:.1}")));
+        vec![
+            self.product.clone(),
+            self.releases.to_string(),
+            self.cycles.to_string(),
+            decimal(self.mean_days_between_releases),
+            decimal(self.median_days_between_releases),
+            decimal(self.patches_per_cycle),
+            decimal(self.mean_cycle_lifespan_days),
+            optional(self.last_release_date),
+            optional(self.days_since_last_release),
+            decimal(self.releases_per_year),
+        ]
+    }
+
+    fn tone(&self) -> Tone {
+        match self.days_since_last_release {
+            Some(days) if days > QUIET_DAYS => Tone::Warning,
+            _ => Tone::Neutral,
+        }
+    }
+}
+
+impl Tabular for InventoryStatus {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "SERVICE",
+            "ENVIRONMENT",
+            "PRODUCT",
+            "CURRENT",
+            "LATEST",
+            "EOL",
+            "STATUS",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.entry.service.clone(),
+            self.entry.environment.clone(),
+            self.entry.product.clone(),
+            self.entry.current_version.clone(),
+            optional(Some(&self.version.latest_version).filter(|v| !v.is_empty())),
+            optional(self.version.eol_date),
+            self.status.label().to_string(),
+        ]
+    }
+
+    fn tone(&self) -> Tone {
+        match self.status {
+            DriftStatus::UpToDate => Tone::Good,
+            DriftStatus::PatchBehind | DriftStatus::MinorBehind => Tone::Warning,
+            DriftStatus::Eol => Tone::Bad,
+            DriftStatus::Unknown => Tone::Neutral,
+        }
+    }
+}
+
+impl Tabular for FindingReport {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "LOCATION",
+            "REFERENCE",
+            "PRODUCT",
+            "CYCLE",
+            "STATUS",
+            "EOL",
+            "LATEST",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            format!("{}:{}", self.finding.file.display(), self.finding.line),
+            self.finding.reference.clone(),
+            optional(self.finding.product.as_deref()),
+            optional(self.cycle.as_deref()),
+            self.status.label().to_string(),
+            optional(self.eol_date),
+            match (&self.latest, self.outdated) {
+                (Some(latest), true) => format!("{latest} (update available)"),
+                (Some(latest), false) => latest.clone(),
+                (None, _) => "-".to_string(),
+            },
+        ]
+    }
+
+    fn tone(&self) -> Tone {
+        match self.status {
+            ScanStatus::Eol => Tone::Bad,
+            ScanStatus::NearEol => Tone::Warning,
+            ScanStatus::Supported if self.outdated => Tone::Warning,
+            ScanStatus::Supported => Tone::Good,
+            ScanStatus::Unknown | ScanStatus::Untracked => Tone::Neutral,
+        }
+    }
+}
+
+impl Tabular for AuditEntry {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "LOCATION",
+            "ECOSYSTEM",
+            "PACKAGE",
+            "CURRENT",
+            "LATEST",
+            "BEHIND",
+            "MAJORS",
+            "AGE",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let status = &self.status;
+        vec![
+            format!("{}:{}", self.file.display(), self.line),
+            status.dependency.ecosystem.label().to_string(),
+            status.dependency.name.clone(),
+            status.dependency.version.clone(),
+            optional(status.latest.as_deref()),
+            optional(status.versions_behind),
+            optional(status.majors_behind),
+            optional(status.age_days.map(|days| format!("{days}d"))),
+        ]
+    }
+
+    fn tone(&self) -> Tone {
+        match (self.status.majors_behind, self.status.versions_behind) {
+            (Some(majors), _) if majors > 0 => Tone::Bad,
+            (_, Some(behind)) if behind > 0 => Tone::Warning,
+            (_, Some(_)) => Tone::Good,
+            _ => Tone::Neutral,
+        }
+    }
+}
+
+fn severity_tone(severity: Severity) -> Tone {
+    match severity {
+        Severity::Pass => Tone::Good,
+        Severity::Warn => Tone::Warning,
+        Severity::Fail => Tone::Bad,
+    }
+}
+
+impl Tabular for Violation {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "LEVEL",
+            "RULE",
+            "SUBJECT",
+            "ENVIRONMENT",
+            "PRODUCT",
+            "VERSION",
+            "MESSAGE",
+            "REMEDIATION",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.effective_severity().label().to_uppercase(),
+            self.rule.clone(),
+            self.subject.clone(),
+            optional(self.environment.as_deref()),
+            self.product.clone(),
+            self.version.clone(),
+            self.message.clone(),
+            match &self.waiver {
+                Some(waiver) => {
+                    format!("waived until {}: {}", waiver.expires, waiver.justification)
+                }
+                None => self.remediation.clone(),
+            },
+        ]
+    }
+
+    fn tone(&self) -> Tone {
+        severity_tone(self.effective_severity())
+    }
+}
+
+impl Tabular for CheckResult {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "LEVEL",
+            "LOCATION",
+            "REFERENCE",
+            "RULE",
+            "MESSAGE",
+            "REMEDIATION",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let finding = &self.report.finding;
+        vec![
+            self.severity.label().to_uppercase(),
+            format!("{}:{}", finding.file.display(), finding.line),
+            finding.reference.clone(),
+            optional(self.rule.as_deref()),
+            optional(self.message.as_deref()),
+            match &self.waiver {
+                Some(waiver) => {
+                    format!("waived until {}: {}", waiver.expires, waiver.justification)
+                }
+                None => optional(self.remediation.as_deref()),
+            },
+        ]
+    }
+
+    fn tone(&self) -> Tone {
+        severity_tone(self.severity)
+    }
+}
+
+impl Tabular for CollectorRun {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "TARGET",
+            "STATUS",
+            "ROWS",
+            "QUARANTINED",
+            "DURATION",
+            "SOURCE",
+            "ERROR",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.target.clone(),
+            self.status.display().to_string(),
+            self.rows.to_string(),
+            self.quarantined.to_string(),
+            format!("{} ms", self.duration_ms),
+            optional(self.source.as_ref()),
+            optional(self.error_message.as_ref()),
+        ]
+    }
+
+    fn tone(&self) -> Tone {
+        match self.status {
+            RunStatus::Active => Tone::Good,
+            RunStatus::Fallback | RunStatus::Degraded => Tone::Warning,
+            RunStatus::TimedOut | RunStatus::Failed => Tone::Bad,
+        }
+    }
+}
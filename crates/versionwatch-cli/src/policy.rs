@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use versionwatch_config::Settings;
+use versionwatch_core::domain::inventory::InventoryEntry;
+use versionwatch_core::domain::lifecycle::Lifecycle;
+use versionwatch_core::domain::policy::{Policy, Severity, Subject, Violation};
+
+use crate::dashboard::CycleStore;
+
+/// Loads the policy given on the command line, or the one set in `config.yaml`.
+pub fn load(config: &Settings, path: Option<&Path>) -> Result<Option<Policy>> {
+    let Some(path) = path.or(config.policy.as_deref()) else {
+        return Ok(None);
+    };
+    let policy = versionwatch_config::load_policy(path)
+        .with_context(|| format!("Could not load policy {}", path.display()))?;
+    Ok(Some(policy))
+}
+
+/// Evaluates every inventory entry against the policy.
+pub fn evaluate_inventory(
+    policy: &Policy,
+    entries: &[InventoryEntry],
+    cycles: &CycleStore,
+    today: chrono::NaiveDate,
+) -> Vec<Violation> {
+    let mut lifecycles: HashMap<&str, Lifecycle> = HashMap::new();
+    let mut violations = Vec::new();
+    for entry in entries {
+        let product = entry.product.as_str();
+        if !lifecycles.contains_key(product) {
+            if let Some(rows) = cycles.get(product) {
+                lifecycles.insert(product, Lifecycle::new(product, rows));
+            }
+        }
+        let Some(lifecycle) = lifecycles.get(product) else {
+            continue;
+        };
+        let subject = Subject {
+            name: &entry.service,
+            product,
+            version: &entry.current_version,
+            environment: Some(&entry.environment),
+            tags: &entry.tags,
+        };
+        violations.extend(policy.evaluate(&subject, lifecycle, today));
+    }
+    violations
+}
+
+/// The worst severity among violations, exceptions taken into account.
+pub fn overall(violations: &[Violation]) -> Severity {
+    violations
+        .iter()
+        .map(Violation::effective_severity)
+        .max()
+        .unwrap_or(Severity::Pass)
+}
+
+/// Warns about exceptions that expired and no longer waive anything.
+pub fn warn_expired(policy: &Policy, today: chrono::NaiveDate) {
+    for exception in policy.expired_exceptions(today) {
+        eprintln!(
+            "⏰ Exception to {} expired on {}: {}",
+            exception.rule, exception.expires, exception.justification
+        );
+    }
+}
+
+/// Prints violation counts and the verdict.
+pub fn print_summary(violations: &[Violation]) {
+    let count = |severity| {
+        violations
+            .iter()
+            .filter(|v| v.effective_severity() == severity)
+            .count()
+    };
+    println!(
+        "\n{} violations: {} failures, {} warnings, {} waived → {}",
+        violations.len(),
+        count(Severity::Fail),
+        count(Severity::Warn),
+        violations.iter().filter(|v| v.waiver.is_some()).count(),
+        overall(violations).label().to_uppercase()
+    );
+}
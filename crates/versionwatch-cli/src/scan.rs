@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use versionwatch_config::Settings;
+use versionwatch_scan::report::{self, FindingReport, ScanStatus};
+use versionwatch_scan::{
+    dockerfile, find_files, manifests, runtimes, tool_versions, workflows, Finding,
+};
+
+use crate::collectors;
+
+/// Scans Dockerfiles (or directories containing them) for base images.
+pub fn dockerfiles(paths: &[PathBuf], build_args: &[String]) -> Result<Vec<Finding>> {
+    let build_args = build_args
+        .iter()
+        .map(|arg| {
+            arg.split_once('=')
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .with_context(|| format!("Invalid build argument `{arg}`, expected NAME=VALUE"))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let mut findings = Vec::new();
+    for file in find_files(paths, dockerfile::is_dockerfile) {
+        findings.extend(dockerfile::scan_file(&file, &build_args)?);
+    }
+    Ok(findings)
+}
+
+/// Scans docker-compose files, Kubernetes manifests and Helm values for images.
+pub fn manifests(paths: &[PathBuf]) -> Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    for file in find_files(paths, manifests::is_manifest) {
+        findings.extend(manifests::scan_file(&file)?);
+    }
+    Ok(findings)
+}
+
+/// Scans runtime pin files such as `.nvmrc`, `go.mod` or `pyproject.toml`,
+/// along with asdf `.tool-versions` and `mise.toml` files.
+pub fn runtimes(paths: &[PathBuf]) -> Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    let files = find_files(paths, |path| {
+        runtimes::is_runtime_file(path) || tool_versions::is_tool_versions(path)
+    });
+    for file in files {
+        if tool_versions::is_tool_versions(&file) {
+            findings.extend(tool_versions::scan_file(&file)?);
+        } else {
+            findings.extend(runtimes::scan_file(&file)?);
+        }
+    }
+    Ok(findings)
+}
+
+/// Scans GitHub Actions workflows for runner images and setup action versions.
+pub fn workflows(paths: &[PathBuf]) -> Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    for file in find_files(paths, workflows::is_workflow) {
+        findings.extend(workflows::scan_file(&file)?);
+    }
+    Ok(findings)
+}
+
+/// Tracked products referenced by the findings, without duplicates.
+pub fn products(findings: &[Finding]) -> Vec<&str> {
+    let mut products: Vec<&str> = findings
+        .iter()
+        .filter_map(|f| f.product.as_deref())
+        .collect();
+    products.sort_unstable();
+    products.dedup();
+    products
+}
+
+/// Collects the products referenced by the findings and evaluates them.
+pub async fn evaluate(
+    config: &Settings,
+    findings: Vec<Finding>,
+    near_eol_days: i64,
+) -> Vec<FindingReport> {
+    let cycles = collectors::collect_products(config, products(&findings)).await;
+
+    let today = chrono::Utc::now().date_naive();
+    report::evaluate(findings, &cycles, today, near_eol_days)
+}
+
+/// Prints findings that need attention as GitHub Actions workflow commands, so
+/// they show up as annotations on the run and in pull request diffs.
+pub fn print_annotations(reports: &[FindingReport]) {
+    for report in reports {
+        let level = match report.status {
+            ScanStatus::Eol => "error",
+            ScanStatus::NearEol => "warning",
+            _ if report.outdated => "warning",
+            _ => continue,
+        };
+        let subject = match &report.cycle {
+            Some(cycle) => format!(
+                "{} {cycle}",
+                report.finding.product.as_deref().unwrap_or_default()
+            ),
+            None => report.finding.reference.clone(),
+        };
+        let mut message = format!("{subject} is {}", report.status.label());
+        match (report.status, report.eol_date) {
+            (ScanStatus::Eol, Some(eol)) => message.push_str(&format!(" since {eol}")),
+            (_, Some(eol)) => message.push_str(&format!(", EOL on {eol}")),
+            _ => {}
+        }
+        if let (Some(latest), true) = (&report.latest, report.outdated) {
+            message.push_str(&format!("; {latest} is available"));
+        }
+
+        println!(
+            "::{level} file={},line={},title={}::{}",
+            escape_property(&report.finding.file.display().to_string()),
+            report.finding.line,
+            escape_property(&format!(
+                "{} {}",
+                report.finding.source, report.finding.reference
+            )),
+            escape_data(&message),
+        );
+    }
+}
+
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
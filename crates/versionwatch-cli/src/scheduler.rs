@@ -0,0 +1,166 @@
+//! Collects targets on their schedules, and on demand.
+
+use chrono::{DateTime, Duration, Utc};
+use futures::stream::{FuturesUnordered, StreamExt};
+use rand::Rng;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use versionwatch_collect::Collector;
+use versionwatch_config::Target;
+use versionwatch_core::domain::collector_run::CollectorRun;
+
+use crate::collectors::create_collector;
+use crate::dashboard::{apply_run, collect_target, AppState};
+
+/// Longest the scheduler sleeps, so it notices the time even when no target
+/// is due for a long while.
+const MAX_SLEEP: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Collects the given targets now, storing each run and sending it on the
+/// returned channel as it completes. Targets already being collected are left
+/// out and returned by name.
+pub fn run_targets(
+    state: &AppState,
+    targets: Vec<(Target, Box<dyn Collector + Send + Sync>)>,
+) -> (mpsc::UnboundedReceiver<CollectorRun>, Vec<String>) {
+    let mut busy = Vec::new();
+    let mut claimed = Vec::new();
+    {
+        let mut running = state.running.lock().expect("running targets poisoned");
+        for (target, collector) in targets {
+            if running.insert(target.name.clone()) {
+                claimed.push((target, collector));
+            } else {
+                busy.push(target.name);
+            }
+        }
+    }
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let state = state.clone();
+    tokio::spawn(async move {
+        let mut collections: FuturesUnordered<_> = claimed
+            .iter()
+            .map(|(target, collector)| {
+                let state = &state;
+                async move {
+                    let baseline = state
+                        .guard
+                        .read()
+                        .await
+                        .baselines
+                        .get(&target.name)
+                        .copied();
+                    let accepted = state
+                        .cycles
+                        .read()
+                        .await
+                        .get(&target.name)
+                        .cloned()
+                        .unwrap_or_default();
+                    let result = collect_target(
+                        &state.config,
+                        &state.limits,
+                        baseline,
+                        &accepted,
+                        target,
+                        collector.as_ref(),
+                    )
+                    .await;
+                    let run = apply_run(state, result).await;
+                    state
+                        .running
+                        .lock()
+                        .expect("running targets poisoned")
+                        .remove(&target.name);
+                    run
+                }
+            })
+            .collect();
+        while let Some(run) = collections.next().await {
+            // Nobody may be listening, e.g. for scheduled runs.
+            let _ = sender.send(run);
+        }
+    });
+    (receiver, busy)
+}
+
+/// Collects every enabled target whenever its schedule comes due. Next run
+/// times are kept in the database when there is one, so they carry over
+/// restarts; targets without one are collected right away.
+pub async fn collect_on_schedule(state: AppState) {
+    let mut persisted = HashMap::new();
+    if let Some(db) = &state.db {
+        match db.list_next_runs().await {
+            Ok(next_runs) => persisted.extend(next_runs),
+            Err(e) => eprintln!("❌ Failed to load collection schedules: {e}"),
+        }
+    }
+    let targets: Vec<&Target> = state.config.targets.iter().filter(|t| t.enabled).collect();
+    let now = Utc::now();
+    state
+        .next_runs
+        .write()
+        .await
+        .extend(targets.iter().map(|t| {
+            (
+                t.name.clone(),
+                persisted.get(&t.name).copied().unwrap_or(now),
+            )
+        }));
+
+    loop {
+        let now = Utc::now();
+        let mut due = Vec::new();
+        {
+            let mut next_runs = state.next_runs.write().await;
+            for &target in &targets {
+                if next_runs.get(&target.name).is_some_and(|at| *at > now) {
+                    continue;
+                }
+                let next_run = next_run(&state, target, now);
+                next_runs.insert(target.name.clone(), next_run);
+                if let Some(db) = &state.db {
+                    if let Err(e) = db.store_next_run(&target.name, next_run).await {
+                        eprintln!("❌ Failed to store {} schedule: {e}", target.name);
+                    }
+                }
+                if let Some(collector) =
+                    create_collector(target, state.config.github_token.as_deref())
+                {
+                    due.push((target.clone(), collector));
+                }
+            }
+        }
+
+        if !due.is_empty() {
+            let (mut runs, busy) = run_targets(&state, due);
+            for name in busy {
+                eprintln!("⏭️  {name}: skipped, the previous collection is still running");
+            }
+            tokio::spawn(async move {
+                while runs.recv().await.is_some() {}
+                println!("📊 Metrics updated successfully");
+            });
+        }
+
+        let earliest = state.next_runs.read().await.values().min().copied();
+        let wait = earliest
+            .and_then(|at| (at - Utc::now()).to_std().ok())
+            .unwrap_or_default()
+            .min(MAX_SLEEP);
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// When the target is collected after `now`, with its jitter applied.
+fn next_run(state: &AppState, target: &Target, now: DateTime<Utc>) -> DateTime<Utc> {
+    let schedule = state.config.schedule_for(target);
+    let jitter = match schedule.jitter_secs {
+        0 => 0,
+        secs => rand::thread_rng().gen_range(0..=secs),
+    };
+    let next = schedule.next_after(now);
+    next.checked_add_signed(Duration::seconds(jitter as i64))
+        .unwrap_or(next)
+}
@@ -0,0 +1,136 @@
+//! Release cadence analytics over the collected data.
+
+use crate::Error;
+use crate::bundle::{self, Dataset};
+use chrono::NaiveDate;
+use polars::prelude::*;
+use serde::Serialize;
+use versionwatch_core::domain::lifecycle::is_prerelease;
+
+/// How often a product ships, computed from its dated stable releases.
+///
+/// Products whose collector reports one row per cycle rather than per release
+/// have no release gaps or patch counts to speak of, so those are `None`.
+#[derive(Serialize, Debug, Clone)]
+pub struct Cadence {
+    pub product: String,
+    /// Collected rows, dated or not, without pre-releases
+    pub releases: u32,
+    pub cycles: u32,
+    pub mean_days_between_releases: Option<f64>,
+    pub median_days_between_releases: Option<f64>,
+    pub patches_per_cycle: Option<f64>,
+    /// Mean days from the first release of a cycle to its end of life, over
+    /// cycles with both dates
+    pub mean_cycle_lifespan_days: Option<f64>,
+    pub last_release_date: Option<NaiveDate>,
+    pub days_since_last_release: Option<i64>,
+    /// Dated releases per year between the first release and today
+    pub releases_per_year: Option<f64>,
+}
+
+/// Computes the release cadence of every product, sorted by product name.
+pub fn cadence(dataset: &Dataset, today: NaiveDate) -> Result<Vec<Cadence>, Error> {
+    // Cycles never include pre-releases, so releases must not either.
+    let dataset: Dataset = dataset
+        .iter()
+        .map(|(product, rows)| {
+            let stable = rows
+                .iter()
+                .filter(|row| !is_prerelease(&row.name))
+                .cloned()
+                .collect();
+            (product.clone(), stable)
+        })
+        .collect();
+
+    let releases = bundle::releases_frame(&dataset)?
+        .lazy()
+        .with_column(col("release_date").cast(DataType::Int32).alias("day"))
+        .sort(
+            ["product", "day"],
+            SortMultipleOptions::default().with_nulls_last(true),
+        )
+        .with_column((col("day") - col("day").shift(lit(1)).over([col("product")])).alias("gap"))
+        .group_by([col("product")])
+        .agg([
+            len().alias("releases"),
+            col("day").count().alias("dated"),
+            col("gap").mean().alias("mean_gap"),
+            col("gap").median().alias("median_gap"),
+            col("day").min().alias("first_day"),
+            col("day").max().alias("last_day"),
+        ]);
+
+    let cycles = bundle::cycles_frame(&dataset, today)?
+        .lazy()
+        .with_column(
+            (col("eol_date").cast(DataType::Int32) - col("release_date").cast(DataType::Int32))
+                .alias("lifespan"),
+        )
+        .group_by([col("product")])
+        .agg([
+            len().alias("cycles"),
+            col("lifespan").mean().alias("mean_lifespan"),
+        ]);
+
+    let frame = releases
+        .join(
+            cycles,
+            [col("product")],
+            [col("product")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .sort(["product"], SortMultipleOptions::default())
+        .collect()?;
+
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+    let today_day = (today - epoch).num_days();
+    let u32_column = |name: &str| -> PolarsResult<Vec<u32>> {
+        let values = frame.column(name)?.cast(&DataType::UInt32)?;
+        Ok(values.u32()?.into_iter().map(|v| v.unwrap_or(0)).collect())
+    };
+    let f64_column = |name: &str| -> PolarsResult<Vec<Option<f64>>> {
+        let values = frame.column(name)?.cast(&DataType::Float64)?;
+        Ok(values.f64()?.into_iter().collect())
+    };
+    let i64_column = |name: &str| -> PolarsResult<Vec<Option<i64>>> {
+        let values = frame.column(name)?.cast(&DataType::Int64)?;
+        Ok(values.i64()?.into_iter().collect())
+    };
+
+    let products = frame.column("product")?.str()?;
+    let release_counts = u32_column("releases")?;
+    let dated = u32_column("dated")?;
+    let cycle_counts = u32_column("cycles")?;
+    let mean_gap = f64_column("mean_gap")?;
+    let median_gap = f64_column("median_gap")?;
+    let mean_lifespan = f64_column("mean_lifespan")?;
+    let first_day = i64_column("first_day")?;
+    let last_day = i64_column("last_day")?;
+
+    let mut cadences = Vec::with_capacity(frame.height());
+    for i in 0..frame.height() {
+        let Some(product) = products.get(i) else {
+            continue;
+        };
+        let years = first_day[i].map(|first| (today_day - first) as f64 / 365.25);
+        let per_release = cycle_counts[i] > 0 && release_counts[i] > cycle_counts[i];
+        cadences.push(Cadence {
+            product: product.to_string(),
+            releases: release_counts[i],
+            cycles: cycle_counts[i],
+            mean_days_between_releases: mean_gap[i].filter(|_| per_release),
+            median_days_between_releases: median_gap[i].filter(|_| per_release),
+            patches_per_cycle: per_release
+                .then(|| release_counts[i] as f64 / cycle_counts[i] as f64),
+            mean_cycle_lifespan_days: mean_lifespan[i],
+            last_release_date: last_day[i].map(|d| epoch + chrono::Duration::days(d)),
+            days_since_last_release: last_day[i].map(|d| today_day - d),
+            releases_per_year: years
+                .filter(|years| *years > 0.0)
+                .map(|years| dated[i] as f64 / years),
+        });
+    }
+    Ok(cadences)
+}
@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use anyhow::anyhow;
 use async_trait::async_trait;
 use polars::prelude::DataFrame;
@@ -26,6 +26,14 @@ impl Collector for ApacheCollector {
         "apache"
     }
 
+    fn source(&self) -> &str {
+        "apache_downloads"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["downloads.apache.org", "archive.apache.org"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let client = reqwest::Client::new();
         let mut versions = BTreeSet::new();
@@ -109,6 +117,9 @@ impl Collector for ApacheCollector {
                 release_date: None,
                 eol_date: None,
                 lts: false,
+                eol_source: EolSource::Official,
+                provenance: None,
+                fallback: false,
             };
             cycles.push(cycle);
         }
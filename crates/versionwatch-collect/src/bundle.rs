@@ -0,0 +1,502 @@
+//! Dataset bundles: the collected data written as typed Parquet, CSV or JSON
+//! files next to a `manifest.json` describing them.
+
+use crate::Error;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use versionwatch_core::domain::collector_run::{CollectorRun, RunStatus};
+use versionwatch_core::domain::lifecycle::Lifecycle;
+use versionwatch_core::domain::product_cycle::{EolSource, ProductCycle};
+
+/// Version of the bundle layout, bumped on incompatible changes.
+pub const BUNDLE_VERSION: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+
+/// Collected rows keyed by product name.
+pub type Dataset = BTreeMap<String, Vec<ProductCycle>>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleFormat {
+    Parquet,
+    Csv,
+    Json,
+}
+
+impl BundleFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BundleFormat::Parquet => "parquet",
+            BundleFormat::Csv => "csv",
+            BundleFormat::Json => "json",
+        }
+    }
+}
+
+/// Describes a bundle and each of its files.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Manifest {
+    pub version: u32,
+    pub format: BundleFormat,
+    pub generated_at: String,
+    /// Where the data came from, e.g. "database" or "collectors"
+    pub source: String,
+    pub tables: Vec<TableFile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableFile {
+    /// Table name: "products", "cycles", "releases" or "history"
+    pub name: String,
+    pub file: String,
+    pub rows: usize,
+    pub columns: Vec<ColumnInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ColumnInfo {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub dtype: String,
+}
+
+/// Collected rows as they came from the collectors, one per release or cycle.
+pub fn releases_frame(dataset: &Dataset) -> PolarsResult<DataFrame> {
+    let rows: Vec<(&String, &ProductCycle)> = dataset
+        .iter()
+        .flat_map(|(product, rows)| rows.iter().map(move |row| (product, row)))
+        .collect();
+
+    DataFrame::new(vec![
+        Column::new(
+            "product".into(),
+            rows.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "name".into(),
+            rows.iter()
+                .map(|(_, r)| r.name.as_str())
+                .collect::<Vec<_>>(),
+        ),
+        date_column("release_date", rows.iter().map(|(_, r)| r.release_date))?,
+        date_column("eol_date", rows.iter().map(|(_, r)| r.eol_date))?,
+        Column::new(
+            "lts".into(),
+            rows.iter().map(|(_, r)| r.lts).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "eol_source".into(),
+            rows.iter()
+                .map(|(_, r)| r.eol_date.map(|_| r.eol_source.label()))
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "provenance".into(),
+            rows.iter()
+                .map(|(_, r)| {
+                    r.provenance
+                        .as_ref()
+                        .and_then(|p| serde_json::to_string(p).ok())
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "fallback".into(),
+            rows.iter().map(|(_, r)| r.fallback).collect::<Vec<_>>(),
+        ),
+    ])
+}
+
+/// Release cycles folded from the collected rows, with their support status.
+pub fn cycles_frame(dataset: &Dataset, today: NaiveDate) -> PolarsResult<DataFrame> {
+    let mut product = Vec::new();
+    let mut name = Vec::new();
+    let mut latest = Vec::new();
+    let mut release_date = Vec::new();
+    let mut latest_release_date = Vec::new();
+    let mut eol_date = Vec::new();
+    let mut eol_source = Vec::new();
+    let mut lts = Vec::new();
+    let mut status = Vec::new();
+
+    for (key, rows) in dataset {
+        let lifecycle = Lifecycle::new(key, rows);
+        for cycle in lifecycle.cycles() {
+            product.push(key.clone());
+            name.push(cycle.name.clone());
+            latest.push(cycle.latest.clone());
+            release_date.push(cycle.release_date);
+            latest_release_date.push(cycle.latest_release_date);
+            eol_date.push(cycle.eol_date);
+            eol_source.push(cycle.eol_date.map(|_| cycle.eol_source.label()));
+            lts.push(cycle.lts);
+            status.push(lifecycle.status(cycle, today).label());
+        }
+    }
+
+    DataFrame::new(vec![
+        Column::new("product".into(), product),
+        Column::new("name".into(), name),
+        Column::new("latest".into(), latest),
+        date_column("release_date", release_date)?,
+        date_column("latest_release_date", latest_release_date)?,
+        date_column("eol_date", eol_date)?,
+        Column::new("eol_source".into(), eol_source),
+        Column::new("lts".into(), lts),
+        Column::new("status".into(), status),
+    ])
+}
+
+/// One row per product with its latest release and row counts.
+pub fn products_frame(dataset: &Dataset) -> PolarsResult<DataFrame> {
+    let lifecycles: Vec<Lifecycle> = dataset
+        .iter()
+        .map(|(product, rows)| Lifecycle::new(product, rows))
+        .collect();
+
+    DataFrame::new(vec![
+        Column::new(
+            "name".into(),
+            lifecycles.iter().map(|l| l.product()).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "latest".into(),
+            lifecycles
+                .iter()
+                .map(|l| l.latest().map(|c| c.latest.as_str()))
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "cycles".into(),
+            lifecycles
+                .iter()
+                .map(|l| l.cycles().len() as u32)
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "releases".into(),
+            dataset
+                .values()
+                .map(|rows| rows.len() as u32)
+                .collect::<Vec<_>>(),
+        ),
+    ])
+}
+
+/// Collection runs of every target, oldest first.
+pub fn history_frame(runs: &[CollectorRun]) -> PolarsResult<DataFrame> {
+    let text = |name: &str, value: fn(&CollectorRun) -> Option<&str>| {
+        Column::new(name.into(), runs.iter().map(value).collect::<Vec<_>>())
+    };
+    DataFrame::new(vec![
+        text("target", |r| Some(&r.target)),
+        Column::new(
+            "started_at".into(),
+            runs.iter()
+                .map(|r| r.started_at.timestamp_millis())
+                .collect::<Vec<_>>(),
+        )
+        .cast(&DataType::Datetime(
+            TimeUnit::Milliseconds,
+            Some(TimeZone::UTC),
+        ))?,
+        Column::new(
+            "duration_ms".into(),
+            runs.iter().map(|r| r.duration_ms).collect::<Vec<_>>(),
+        ),
+        text("status", |r| Some(r.status.label())),
+        Column::new(
+            "rows".into(),
+            runs.iter().map(|r| r.rows as u32).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "quarantined".into(),
+            runs.iter()
+                .map(|r| r.quarantined as u32)
+                .collect::<Vec<_>>(),
+        ),
+        text("error_kind", |r| r.error_kind.as_deref()),
+        text("error_message", |r| r.error_message.as_deref()),
+        text("source", |r| r.source.as_deref()),
+    ])
+}
+
+fn date_column(
+    name: &str,
+    dates: impl IntoIterator<Item = Option<NaiveDate>>,
+) -> PolarsResult<Column> {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+    let days: Vec<Option<i32>> = dates
+        .into_iter()
+        .map(|d| d.map(|d| (d - epoch).num_days() as i32))
+        .collect();
+    Column::new(name.into(), days).cast(&DataType::Date)
+}
+
+/// Writes the dataset to `dir` as products, cycles and releases tables, and the
+/// collection runs as a history table, plus a manifest.
+pub fn write_bundle(
+    dir: &Path,
+    format: BundleFormat,
+    dataset: &Dataset,
+    history: &[CollectorRun],
+    source: &str,
+) -> Result<Manifest, Error> {
+    std::fs::create_dir_all(dir)?;
+    let today = Utc::now().date_naive();
+    let tables = [
+        ("products", products_frame(dataset)?),
+        ("cycles", cycles_frame(dataset, today)?),
+        ("releases", releases_frame(dataset)?),
+        ("history", history_frame(history)?),
+    ];
+
+    let mut files = Vec::new();
+    for (name, mut frame) in tables {
+        let file = format!("{name}.{}", format.extension());
+        let mut writer = File::create(dir.join(&file))?;
+        match format {
+            BundleFormat::Parquet => {
+                ParquetWriter::new(&mut writer).finish(&mut frame)?;
+            }
+            BundleFormat::Csv => CsvWriter::new(&mut writer).finish(&mut frame)?,
+            BundleFormat::Json => JsonWriter::new(&mut writer)
+                .with_json_format(JsonFormat::Json)
+                .finish(&mut frame)?,
+        }
+        files.push(TableFile {
+            name: name.to_string(),
+            file,
+            rows: frame.height(),
+            columns: frame
+                .schema()
+                .iter()
+                .map(|(name, dtype)| ColumnInfo {
+                    name: name.to_string(),
+                    dtype: dtype.to_string(),
+                })
+                .collect(),
+        });
+    }
+
+    let manifest = Manifest {
+        version: BUNDLE_VERSION,
+        format,
+        generated_at: Utc::now().to_rfc3339(),
+        source: source.to_string(),
+        tables: files,
+    };
+    std::fs::write(dir.join(MANIFEST), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(manifest)
+}
+
+/// Reads the manifest of a bundle.
+pub fn read_manifest(dir: &Path) -> Result<Manifest, Error> {
+    let manifest: Manifest = serde_json::from_str(&std::fs::read_to_string(dir.join(MANIFEST))?)?;
+    if manifest.version > BUNDLE_VERSION {
+        return Err(Error::Other(anyhow::anyhow!(
+            "bundle version {} is newer than the supported version {BUNDLE_VERSION}",
+            manifest.version
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Reads one table of a bundle, with the column types recorded in its manifest
+/// whatever the format.
+pub fn read_table(dir: &Path, manifest: &Manifest, table: &str) -> Result<DataFrame, Error> {
+    let entry = manifest
+        .tables
+        .iter()
+        .find(|t| t.name == table)
+        .ok_or_else(|| Error::Other(anyhow::anyhow!("bundle has no {table} table")))?;
+    let path = dir.join(&entry.file);
+
+    // CSV and JSON carry no types, and inferring them turns versions such as
+    // "8.0" into floats: read with the manifest's types instead.
+    let schema = Arc::new(text_schema(entry));
+    let mut frame = match manifest.format {
+        BundleFormat::Parquet => ParquetReader::new(File::open(&path)?).finish()?,
+        BundleFormat::Csv => CsvReadOptions::default()
+            .with_has_header(true)
+            .with_schema(Some(schema))
+            .try_into_reader_with_file_path(Some(path))?
+            .finish()?,
+        BundleFormat::Json => JsonReader::new(File::open(&path)?)
+            .with_schema(schema)
+            .finish()?,
+    };
+
+    for column in &entry.columns {
+        if column.dtype == "date" && frame.column(&column.name)?.dtype() != &DataType::Date {
+            let typed = frame.column(&column.name)?.cast(&DataType::Date)?;
+            frame.with_column(typed)?;
+        }
+    }
+    Ok(frame)
+}
+
+/// The schema of a table as recorded in the manifest, with dates and every
+/// type other than booleans and numbers read as text.
+fn text_schema(entry: &TableFile) -> Schema {
+    entry
+        .columns
+        .iter()
+        .map(|column| {
+            let dtype = match column.dtype.as_str() {
+                "bool" => DataType::Boolean,
+                "i32" => DataType::Int32,
+                "i64" => DataType::Int64,
+                "u32" => DataType::UInt32,
+                "u64" => DataType::UInt64,
+                "f64" => DataType::Float64,
+                _ => DataType::String,
+            };
+            Field::new(column.name.as_str().into(), dtype)
+        })
+        .collect()
+}
+
+/// Reads the collected rows back from a bundle.
+pub fn read_bundle(dir: &Path) -> Result<(Manifest, Dataset), Error> {
+    let manifest = read_manifest(dir)?;
+    let releases = read_table(dir, &manifest, "releases")?;
+
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+    let date = |name: &str| -> PolarsResult<Vec<Option<NaiveDate>>> {
+        let physical = releases.column(name)?.cast(&DataType::Int32)?;
+        Ok(physical
+            .i32()?
+            .into_iter()
+            .map(|days| days.map(|d| epoch + chrono::Duration::days(d as i64)))
+            .collect())
+    };
+    let products = releases.column("product")?.str()?;
+    let names = releases.column("name")?.str()?;
+    let release_dates = date("release_date")?;
+    let eol_dates = date("eol_date")?;
+    let lts = releases.column("lts")?.bool()?;
+    // Bundles written before EOL sources, provenance and fallback data were
+    // tracked lack those columns.
+    let eol_sources = releases
+        .column("eol_source")
+        .ok()
+        .map(|column| column.cast(&DataType::String))
+        .transpose()?;
+    let eol_sources = eol_sources
+        .as_ref()
+        .map(|column| column.str())
+        .transpose()?;
+    let provenance = releases
+        .column("provenance")
+        .ok()
+        .map(|column| column.cast(&DataType::String))
+        .transpose()?;
+    let provenance = provenance.as_ref().map(|column| column.str()).transpose()?;
+    let fallback = releases
+        .column("fallback")
+        .ok()
+        .map(|column| column.bool())
+        .transpose()?;
+
+    let mut dataset = Dataset::new();
+    for i in 0..releases.height() {
+        let (Some(product), Some(name)) = (products.get(i), names.get(i)) else {
+            continue;
+        };
+        dataset
+            .entry(product.to_string())
+            .or_default()
+            .push(ProductCycle {
+                name: name.to_string(),
+                release_date: release_dates[i],
+                eol_date: eol_dates[i],
+                lts: lts.get(i).unwrap_or(false),
+                eol_source: eol_sources
+                    .and_then(|sources| sources.get(i))
+                    .map(EolSource::from_label)
+                    .unwrap_or_default(),
+                provenance: provenance
+                    .and_then(|p| p.get(i))
+                    .and_then(|json| serde_json::from_str(json).ok()),
+                fallback: fallback.and_then(|f| f.get(i)).unwrap_or(false),
+            });
+    }
+    Ok((manifest, dataset))
+}
+
+/// Reads the collection runs back from a bundle; bundles written before
+/// history was exported have none.
+pub fn read_history(dir: &Path) -> Result<Vec<CollectorRun>, Error> {
+    let manifest = read_manifest(dir)?;
+    if !manifest.tables.iter().any(|t| t.name == "history") {
+        return Ok(Vec::new());
+    }
+    let history = read_table(dir, &manifest, "history")?;
+
+    // Parquet keeps the timestamp type; JSON holds RFC 3339 text and CSV the
+    // UTC time without an offset.
+    let started_at: Vec<Option<DateTime<Utc>>> = match history.column("started_at")?.dtype() {
+        DataType::Datetime(..) => history
+            .column("started_at")?
+            .cast(&DataType::Int64)?
+            .i64()?
+            .into_iter()
+            .map(|ms| ms.and_then(DateTime::from_timestamp_millis))
+            .collect(),
+        _ => history
+            .column("started_at")?
+            .str()?
+            .into_iter()
+            .map(|text| match DateTime::parse_from_rfc3339(text?) {
+                Ok(parsed) => Some(parsed.with_timezone(&Utc)),
+                Err(_) => NaiveDateTime::parse_from_str(text?, "%Y-%m-%dT%H:%M:%S%.f")
+                    .ok()
+                    .map(|t| t.and_utc()),
+            })
+            .collect(),
+    };
+    let text = |name: &str| -> PolarsResult<Vec<Option<String>>> {
+        let column = history.column(name)?.cast(&DataType::String)?;
+        Ok(column
+            .str()?
+            .into_iter()
+            .map(|v| v.map(str::to_string))
+            .collect())
+    };
+    let count = |name: &str| -> PolarsResult<Vec<Option<u64>>> {
+        let column = history.column(name)?.cast(&DataType::UInt64)?;
+        Ok(column.u64()?.into_iter().collect())
+    };
+    let targets = text("target")?;
+    let statuses = text("status")?;
+    let durations = count("duration_ms")?;
+    let rows = count("rows")?;
+    let quarantined = count("quarantined")?;
+    let error_kinds = text("error_kind")?;
+    let error_messages = text("error_message")?;
+    let sources = text("source")?;
+
+    let mut runs = Vec::new();
+    for i in 0..history.height() {
+        let (Some(target), Some(started_at)) = (&targets[i], started_at[i]) else {
+            continue;
+        };
+        runs.push(CollectorRun {
+            target: target.clone(),
+            started_at,
+            duration_ms: durations[i].unwrap_or_default(),
+            status: RunStatus::from_label(statuses[i].as_deref().unwrap_or_default()),
+            rows: rows[i].unwrap_or_default() as usize,
+            quarantined: quarantined[i].unwrap_or_default() as usize,
+            error_kind: error_kinds[i].clone(),
+            error_message: error_messages[i].clone(),
+            source: sources[i].clone(),
+        });
+    }
+    Ok(runs)
+}
@@ -1,124 +1,56 @@
+use crate::sources::{SourceChain, SourceDefaults, SourceTag};
 use crate::{Collector, Error, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use polars::prelude::DataFrame;
 use regex::Regex;
 use semver::Version;
 use std::collections::HashSet;
-use versionwatch_core::domain::product_cycle::ProductCycle;
+use versionwatch_config::{SourceKind, Target};
+use versionwatch_core::domain::product_cycle::{EolSource, ProductCycle};
 
-#[derive(serde::Deserialize, Debug)]
-struct GitHubTag {
-    name: String,
-}
-
-#[derive(serde::Deserialize, Debug)]
-struct DockerHubTag {
-    name: String,
-}
+const GITHUB_REPOSITORY: &str = "moby/moby";
+const DOCKER_IMAGE: &str = "library/docker";
 
-#[derive(serde::Deserialize, Debug)]
-struct DockerHubResponse {
-    results: Vec<DockerHubTag>,
-}
+const SOURCES: SourceDefaults = SourceDefaults {
+    repository: GITHUB_REPOSITORY,
+    image: DOCKER_IMAGE,
+    fallback: None,
+    chain: &[SourceKind::GithubTags, SourceKind::Dockerhub],
+};
 
 #[derive(Debug)]
 pub struct DockerCollector {
     name: String,
-    github_token: Option<String>,
+    sources: SourceChain,
 }
 
 impl DockerCollector {
     pub fn new(name: &str) -> Self {
+        let token = std::env::var("GITHUB_TOKEN").ok();
         Self {
             name: name.to_string(),
-            github_token: std::env::var("GITHUB_TOKEN").ok(),
-        }
-    }
-
-    async fn fetch_github_tags(&self) -> Result<Vec<GitHubTag>, Error> {
-        let url = "https://api.github.com/repos/moby/moby/tags?per_page=100";
-        let client = reqwest::Client::new();
-
-        let mut request = client
-            .get(url)
-            .header("User-Agent", "versionwatch-collector")
-            .header("Accept", "application/vnd.github.v3+json");
-
-        if let Some(token) = &self.github_token {
-            request = request.bearer_auth(token);
-        }
-
-        let response = request.send().await?;
-
-        if !response.status().is_success() {
-            return match response.status() {
-                reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS => {
-                    println!("DEBUG: GitHub API rate limited for Docker, trying Docker Hub");
-                    self.fetch_docker_tags().await
-                }
-                other => Err(Error::Other(anyhow::anyhow!(
-                    "GitHub API returned unexpected status: {}",
-                    other
-                ))),
-            };
+            sources: SourceChain::from_defaults(&SOURCES, token.as_deref()),
         }
-
-        let tags: Vec<GitHubTag> = response.json().await?;
-        Ok(tags)
     }
 
-    async fn fetch_docker_tags(&self) -> Result<Vec<GitHubTag>, Error> {
-        let url = "https://hub.docker.com/v2/repositories/library/docker/tags/?page_size=500";
-        let client = reqwest::Client::new();
-
-        let response = client
-            .get(url)
-            .header("User-Agent", "versionwatch-collector")
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(Error::Other(anyhow::anyhow!(
-                "Docker Hub API returned status: {}",
-                response.status()
-            )));
-        }
-
-        let docker_response: DockerHubResponse = response.json().await?;
-
-        // Convert DockerHubTag to GitHubTag format for consistency
-        let tags = docker_response
-            .results
-            .into_iter()
-            .map(|tag| GitHubTag { name: tag.name })
-            .collect();
-
-        Ok(tags)
+    /// A collector reading the sources the target configures.
+    pub fn from_target(target: &Target, github_token: Option<&str>) -> Result<Self, Error> {
+        let token = github_token
+            .map(str::to_string)
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok());
+        Ok(Self {
+            name: target.name.clone(),
+            sources: SourceChain::for_target(target, token.as_deref(), &SOURCES)?,
+        })
     }
-}
-
-impl Default for DockerCollector {
-    fn default() -> Self {
-        Self::new("docker")
-    }
-}
-
-#[async_trait]
-impl Collector for DockerCollector {
-    fn name(&self) -> &str {
-        &self.name
-    }
-
-    async fn collect(&self) -> Result<DataFrame, Error> {
-        let tags = self.fetch_github_tags().await?;
 
+    fn parse_tags(&self, tags: Vec<SourceTag>) -> Vec<ProductCycle> {
         // Regex to match Docker version tags like "v27.3.1", "v26.1.4", etc.
         let re = Regex::new(r"^v?(\d+\.\d+\.\d+)(?:-.*)?$").unwrap();
 
         let mut seen_versions = HashSet::new();
 
-        let cycles: Vec<ProductCycle> = tags
-            .into_iter()
+        tags.into_iter()
             .filter_map(|tag| {
                 if let Some(captures) = re.captures(&tag.name) {
                     let version_str = captures.get(1)?.as_str();
@@ -137,9 +69,12 @@ impl Collector for DockerCollector {
 
                         Some(ProductCycle {
                             name: version.to_string(),
-                            release_date: None, // Docker Hub doesn't provide release dates
+                            release_date: tag.date,
                             eol_date: None,
                             lts: false,
+                            eol_source: EolSource::Official,
+                            provenance: None,
+                            fallback: false,
                         })
                     } else {
                         None
@@ -148,11 +83,35 @@ impl Collector for DockerCollector {
                     None
                 }
             })
-            .collect();
+            .collect()
+    }
+}
 
-        if cycles.is_empty() {
-            return Err(Error::NotFound);
-        }
+impl Default for DockerCollector {
+    fn default() -> Self {
+        Self::new("docker")
+    }
+}
+
+#[async_trait]
+impl Collector for DockerCollector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn source(&self) -> &str {
+        self.sources.primary()
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        self.sources.hosts()
+    }
+
+    async fn collect(&self) -> Result<DataFrame, Error> {
+        let cycles = self
+            .sources
+            .collect(&self.name, |tags| self.parse_tags(tags))
+            .await?;
 
         product_cycles_to_dataframe(cycles).map_err(Error::from)
     }
@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use polars::prelude::DataFrame;
 use serde::Deserialize;
@@ -55,6 +55,14 @@ impl Collector for EclipseTemurinCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "adoptium_api"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["api.adoptium.net", "api.github.com"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let mut headers = reqwest::header::HeaderMap::new();
 
@@ -118,6 +126,9 @@ impl Collector for EclipseTemurinCollector {
                         release_date,
                         eol_date: None,
                         lts: v.optional.as_deref() == Some("LTS"),
+                        eol_source: EolSource::Official,
+                        provenance: None,
+                        fallback: false,
                     }
                 }
             })
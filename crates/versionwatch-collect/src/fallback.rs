@@ -0,0 +1,37 @@
+//! Curated lists of known versions collectors serve when every live source
+//! fails. Built-in datasets ship in `data/fallback`; a target can point to its
+//! own file instead.
+
+use crate::Error;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Known versions of a product and when the list was last curated.
+///
+/// ```yaml
+/// curated: 2024-12-19
+/// versions: ["6.0.3", "6.0.2"]
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct FallbackDataset {
+    pub curated: NaiveDate,
+    pub versions: Vec<String>,
+}
+
+impl FallbackDataset {
+    /// The dataset shipped for a product, if any.
+    pub fn builtin(product: &str) -> Option<Self> {
+        let yaml = match product {
+            "swift" => include_str!("../data/fallback/swift.yaml"),
+            _ => return None,
+        };
+        Some(serde_yaml::from_str(yaml).expect("built-in fallback datasets are valid"))
+    }
+
+    /// Reads a dataset file.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let yaml = std::fs::read_to_string(path)?;
+        Ok(serde_yaml::from_str(&yaml)?)
+    }
+}
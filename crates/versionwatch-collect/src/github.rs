@@ -7,7 +7,7 @@ use regex::Regex;
 use semver::Version;
 use serde::Deserialize;
 use std::time::Duration;
-use versionwatch_core::domain::product_cycle::ProductCycle;
+use versionwatch_core::domain::product_cycle::{EolSource, ProductCycle};
 
 #[derive(Debug, Clone)]
 pub enum GitHubSource {
@@ -71,6 +71,17 @@ impl Collector for GitHubCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        match self.source {
+            GitHubSource::Releases => "github_releases",
+            GitHubSource::Tags => "github_tags",
+        }
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["api.github.com"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         match self.source {
             GitHubSource::Releases => self.collect_from_releases().await,
@@ -103,6 +114,9 @@ impl GitHubCollector {
                         release_date,
                         eol_date: None,
                         lts: false,
+                        eol_source: EolSource::Official,
+                        provenance: None,
+                        fallback: false,
                     });
                 }
             }
@@ -157,6 +171,9 @@ impl GitHubCollector {
                             release_date,
                             eol_date: None,
                             lts: false,
+                            eol_source: EolSource::Official,
+                            provenance: None,
+                            fallback: false,
                         })
                     } else {
                         None
@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use polars::prelude::DataFrame;
@@ -25,6 +25,14 @@ impl Collector for GoCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "go_release_history"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["go.dev"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let history_html = reqwest::get(GO_HISTORY_URL).await?.text().await?;
         let document = scraper::Html::parse_document(&history_html);
@@ -39,15 +47,18 @@ impl Collector for GoCollector {
             let date_str = cap.get(2).unwrap().as_str();
 
             if let Ok(date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                if version_str.ends_with(".0") {
-                    if let Some(major_version) = version_str.rsplitn(2, '.').last() {
-                        major_release_dates.insert(major_version.to_string(), date);
-                    }
+                // Go 1.21 and later name their first release "1.21.0", earlier ones "1.20".
+                let major_version = version_str.strip_suffix(".0").unwrap_or(version_str);
+                if major_version.split('.').count() == 2 {
+                    major_release_dates.insert(major_version.to_string(), date);
                 }
                 release_dates.insert(version_str.to_string(), date);
             }
         }
 
+        // Each major Go release is supported until there are two newer major
+        // releases, so a cycle ends when minor + 2 ships. The newest cycles are
+        // left to the support policy.
         let cycles = release_dates
             .into_iter()
             .filter(|(version, _)| !version.contains("rc"))
@@ -69,6 +80,9 @@ impl Collector for GoCollector {
                     release_date: Some(release_date),
                     eol_date,
                     lts: false,
+                    eol_source: EolSource::Official,
+                    provenance: None,
+                    fallback: false,
                 }
             })
             .collect::<Vec<_>>();
@@ -1,5 +1,6 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use polars::prelude::DataFrame;
 use serde::Deserialize;
 
@@ -9,6 +10,7 @@ const KOTLIN_RELEASES_URL: &str = "https://api.github.com/repos/JetBrains/kotlin
 struct GitHubRelease {
     tag_name: String,
     prerelease: bool,
+    published_at: Option<DateTime<Utc>>,
 }
 
 pub struct KotlinCollector {
@@ -31,6 +33,14 @@ impl Collector for KotlinCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "github_releases"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["api.github.com"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let client = reqwest::Client::new();
 
@@ -75,9 +85,12 @@ impl Collector for KotlinCollector {
             {
                 let cycle = ProductCycle {
                     name: version.to_string(),
-                    release_date: None,
+                    release_date: release.published_at.map(|d| d.date_naive()),
                     eol_date: None,
                     lts: false,
+                    eol_source: EolSource::Official,
+                    provenance: None,
+                    fallback: false,
                 };
                 cycles.push(cycle);
             }
@@ -2,16 +2,20 @@ use async_trait::async_trait;
 use chrono::NaiveDate;
 use polars::prelude::*;
 use thiserror::Error;
-use versionwatch_core::domain::product_cycle::ProductCycle;
+use versionwatch_core::domain::product_cycle::{EolSource, ProductCycle};
 
+pub mod analytics;
 pub mod apache;
+pub mod bundle;
 pub mod caddy;
 pub mod docker;
 pub mod eclipse_temurin;
+pub mod fallback;
 pub mod github;
 pub mod go;
 pub mod kong;
 pub mod kotlin;
+pub mod limits;
 pub mod mongodb;
 pub mod mysql;
 pub mod nginx;
@@ -20,10 +24,15 @@ pub mod perl;
 pub mod php;
 pub mod postgresql;
 pub mod python;
+pub mod query;
+pub mod reconcile;
+pub mod registry;
 pub mod ruby;
 pub mod rust;
 pub mod scala;
+pub mod sources;
 pub mod swift;
+pub mod validate;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -31,6 +40,8 @@ pub enum Error {
     NotFound,
     #[error("rate limited: {0}")]
     RateLimited(String),
+    #[error("schema mismatch: {0}")]
+    Schema(String),
     #[error(transparent)]
     Config(#[from] config::ConfigError),
     #[error(transparent)]
@@ -53,6 +64,29 @@ pub enum Error {
     InvalidToken,
     #[error(transparent)]
     Polars(#[from] PolarsError),
+    #[error("timed out after {}s", .0.as_secs())]
+    Timeout(std::time::Duration),
+}
+
+impl Error {
+    /// Category of the error, recorded with failed collector runs.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::NotFound => "not_found",
+            Error::RateLimited(_) => "rate_limited",
+            Error::Schema(_) => "schema",
+            Error::Timeout(_) => "timeout",
+            Error::Reqwest(e) if e.is_timeout() => "timeout",
+            Error::Reqwest(_) => "network",
+            Error::SemVer(_) | Error::SerdeYaml(_) | Error::SerdeJson(_) | Error::Rss(_) => "parse",
+            Error::Utf8(_) => "parse",
+            Error::InvalidToken => "invalid_token",
+            Error::Config(_) => "config",
+            Error::Io(_) => "io",
+            Error::Polars(_) => "dataframe",
+            Error::Other(_) => "other",
+        }
+    }
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -81,6 +115,16 @@ pub trait Collector: Send + Sync {
     /// Returns the name of the software this collector tracks
     fn name(&self) -> &str;
 
+    /// Hosts the collector fetches from, so concurrent collections can be
+    /// limited per host
+    fn hosts(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    /// Source the collector reads from, e.g. "github_releases", recorded as
+    /// the provenance of rows that do not carry their own
+    fn source(&self) -> &str;
+
     /// Collects version data and returns it as a Polars DataFrame
     ///
     /// The DataFrame should contain the following columns:
@@ -119,12 +163,25 @@ pub fn product_cycles_to_dataframe(cycles: Vec<ProductCycle>) -> PolarsResult<Da
         })
         .collect();
     let lts_flags: Vec<bool> = cycles.iter().map(|c| c.lts).collect();
+    let eol_sources: Vec<&str> = cycles.iter().map(|c| c.eol_source.label()).collect();
+    let provenance: Vec<Option<String>> = cycles
+        .iter()
+        .map(|c| {
+            c.provenance
+                .as_ref()
+                .and_then(|p| serde_json::to_string(p).ok())
+        })
+        .collect();
+    let fallback: Vec<bool> = cycles.iter().map(|c| c.fallback).collect();
 
     df!(
         "name" => names,
         "release_date" => release_dates,
         "eol_date" => eol_dates,
         "lts" => lts_flags,
+        "eol_source" => eol_sources,
+        "provenance" => provenance,
+        "fallback" => fallback,
     )
 }
 
@@ -133,18 +190,24 @@ pub fn product_cycles_to_dataframe(cycles: Vec<ProductCycle>) -> PolarsResult<Da
 /// This utility function helps with backward compatibility during the migration.
 /// It extracts ProductCycle structs from a properly formatted DataFrame.
 pub fn dataframe_to_product_cycles(df: &DataFrame) -> PolarsResult<Vec<ProductCycle>> {
-    let names = df
-        .column("name")?
-        .str()?
-        .into_no_null_iter()
-        .collect::<Vec<_>>();
+    // Nulls are tolerated here; `validate` quarantines the rows that have them.
+    let names = df.column("name")?.str()?;
     let release_dates = df.column("release_date")?.i32()?;
     let eol_dates = df.column("eol_date")?.i32()?;
-    let lts_flags = df
-        .column("lts")?
-        .bool()?
-        .into_no_null_iter()
-        .collect::<Vec<_>>();
+    let lts_flags = df.column("lts")?.bool()?;
+    // Collectors building their own frame may leave the source out.
+    let eol_sources = match df.column("eol_source") {
+        Ok(column) => Some(column.str()?),
+        Err(_) => None,
+    };
+    let provenance = match df.column("provenance") {
+        Ok(column) => Some(column.str()?),
+        Err(_) => None,
+    };
+    let fallback = match df.column("fallback") {
+        Ok(column) => Some(column.bool()?),
+        Err(_) => None,
+    };
 
     let mut cycles = Vec::new();
     let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
@@ -158,10 +221,18 @@ pub fn dataframe_to_product_cycles(df: &DataFrame) -> PolarsResult<Vec<ProductCy
             .map(|days| epoch + chrono::Duration::days(days as i64));
 
         cycles.push(ProductCycle {
-            name: names[i].to_string(),
+            name: names.get(i).unwrap_or_default().to_string(),
             release_date,
             eol_date,
-            lts: lts_flags[i],
+            lts: lts_flags.get(i).unwrap_or(false),
+            eol_source: eol_sources
+                .and_then(|sources| sources.get(i))
+                .map(EolSource::from_label)
+                .unwrap_or_default(),
+            provenance: provenance
+                .and_then(|p| p.get(i))
+                .and_then(|json| serde_json::from_str(json).ok()),
+            fallback: fallback.and_then(|f| f.get(i)).unwrap_or(false),
         });
     }
 
@@ -0,0 +1,84 @@
+//! Bounds how many collections run at once, overall and per host, and how
+//! long each may take.
+
+use crate::{Collector, Error};
+use chrono::{DateTime, Utc};
+use polars::prelude::DataFrame;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use versionwatch_config::Collection;
+
+/// Permits shared by every collection of a process.
+#[derive(Debug)]
+pub struct CollectionLimits {
+    global: Arc<Semaphore>,
+    per_host: usize,
+    host_limits: HashMap<String, usize>,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+/// The outcome of a collection, timed from when it got its permits.
+#[derive(Debug)]
+pub struct TimedCollection {
+    pub result: Result<DataFrame, Error>,
+    pub started_at: DateTime<Utc>,
+    pub duration: Duration,
+}
+
+impl CollectionLimits {
+    pub fn new(settings: &Collection) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(settings.max_concurrency.max(1))),
+            per_host: settings.per_host.max(1),
+            host_limits: settings.hosts.clone(),
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn host(&self, host: &str) -> Arc<Semaphore> {
+        let mut hosts = self.hosts.lock().expect("host limits poisoned");
+        hosts
+            .entry(host.to_string())
+            .or_insert_with(|| {
+                let limit = self.host_limits.get(host).copied().unwrap_or(self.per_host);
+                Arc::new(Semaphore::new(limit.max(1)))
+            })
+            .clone()
+    }
+
+    /// Runs a collection once a global permit and a permit for each of its
+    /// hosts are free, failing it with [`Error::Timeout`] if it takes longer
+    /// than `timeout`. Time spent waiting for permits is not counted.
+    pub async fn run(&self, collector: &dyn Collector, timeout: Duration) -> TimedCollection {
+        // Permits are taken in host order, and the global one last, so
+        // collections cannot each hold one the other waits for, and one
+        // waiting on a busy host does not keep others from running.
+        let mut hosts = collector.hosts();
+        hosts.sort_unstable();
+        hosts.dedup();
+
+        let mut permits = Vec::with_capacity(hosts.len());
+        for host in hosts {
+            let permit = self
+                .host(host)
+                .acquire_owned()
+                .await
+                .expect("semaphore closed");
+            permits.push(permit);
+        }
+        let _global = self.global.acquire().await.expect("semaphore closed");
+
+        let started_at = Utc::now();
+        let start = Instant::now();
+        let result = tokio::time::timeout(timeout, collector.collect())
+            .await
+            .unwrap_or(Err(Error::Timeout(timeout)));
+        TimedCollection {
+            result,
+            started_at,
+            duration: start.elapsed(),
+        }
+    }
+}
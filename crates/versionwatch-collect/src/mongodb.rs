@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use polars::prelude::DataFrame;
@@ -25,6 +25,14 @@ impl Collector for MongoDbCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "mongodb_lifecycles"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["www.mongodb.com"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let response = reqwest::get(MONGODB_LIFECYCLE_URL).await?.text().await?;
         let document = Html::parse_document(&response);
@@ -62,6 +70,9 @@ impl Collector for MongoDbCollector {
                         release_date: None,
                         eol_date: Some(eol_date),
                         lts: false, // Info not available on this page
+                        eol_source: EolSource::Official,
+                        provenance: None,
+                        fallback: false,
                     });
                 }
             }
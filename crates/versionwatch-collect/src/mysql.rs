@@ -1,128 +1,60 @@
+use crate::sources::{SourceChain, SourceDefaults, SourceTag};
 use crate::{Collector, Error, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use polars::prelude::DataFrame;
 use regex::Regex;
 use semver::Version;
 use std::collections::HashSet;
-use versionwatch_core::domain::product_cycle::ProductCycle;
+use versionwatch_config::{SourceKind, Target};
+use versionwatch_core::domain::product_cycle::{EolSource, ProductCycle};
 
-#[derive(serde::Deserialize, Debug)]
-struct GitHubTag {
-    name: String,
-}
-
-#[derive(serde::Deserialize, Debug)]
-struct DockerHubTag {
-    name: String,
-}
+const GITHUB_REPOSITORY: &str = "mysql/mysql-server";
+const DOCKER_IMAGE: &str = "library/mysql";
 
-#[derive(serde::Deserialize, Debug)]
-struct DockerHubResponse {
-    results: Vec<DockerHubTag>,
-}
+const SOURCES: SourceDefaults = SourceDefaults {
+    repository: GITHUB_REPOSITORY,
+    image: DOCKER_IMAGE,
+    fallback: None,
+    chain: &[SourceKind::GithubTags, SourceKind::Dockerhub],
+};
 
 #[derive(Debug)]
 pub struct MySqlCollector {
     name: String,
-    github_token: Option<String>,
+    sources: SourceChain,
 }
 
 impl MySqlCollector {
     pub fn new(name: &str) -> Self {
         Self {
             name: name.to_string(),
-            github_token: None,
+            sources: SourceChain::from_defaults(&SOURCES, None),
         }
     }
 
     pub fn with_token(name: &str, token: String) -> Self {
         Self {
             name: name.to_string(),
-            github_token: Some(token),
-        }
-    }
-
-    async fn fetch_tags(&self) -> Result<Vec<GitHubTag>, Error> {
-        let url = "https://api.github.com/repos/mysql/mysql-server/tags?per_page=100";
-        let client = reqwest::Client::new();
-
-        let mut request = client
-            .get(url)
-            .header("User-Agent", "versionwatch-collector")
-            .header("Accept", "application/vnd.github.v3+json");
-
-        // Only add token if available
-        if let Some(token) = &self.github_token {
-            request = request.bearer_auth(token);
-        }
-
-        let response = request.send().await?;
-
-        if !response.status().is_success() {
-            return match response.status() {
-                reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS => {
-                    // For rate limiting, we'll try Docker Hub as alternative
-                    println!("DEBUG: GitHub API rate limited, trying Docker Hub");
-                    self.fetch_docker_tags().await
-                }
-                other => Err(Error::Other(anyhow::anyhow!(
-                    "GitHub API returned unexpected status: {}",
-                    other
-                ))),
-            };
+            sources: SourceChain::from_defaults(&SOURCES, Some(&token)),
         }
-
-        let tags: Vec<GitHubTag> = response.json().await?;
-        Ok(tags)
     }
 
-    async fn fetch_docker_tags(&self) -> Result<Vec<GitHubTag>, Error> {
-        let url = "https://hub.docker.com/v2/repositories/library/mysql/tags/?page_size=500";
-        let client = reqwest::Client::new();
-
-        let response = client
-            .get(url)
-            .header("User-Agent", "versionwatch-collector")
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(Error::Other(anyhow::anyhow!(
-                "Docker Hub API returned status: {}",
-                response.status()
-            )));
-        }
-
-        let docker_response: DockerHubResponse = response.json().await?;
-
-        // Convert DockerHubTag to GitHubTag format for consistency
-        let tags = docker_response
-            .results
-            .into_iter()
-            .map(|tag| GitHubTag { name: tag.name })
-            .collect();
-
-        Ok(tags)
+    /// A collector reading the sources the target configures.
+    pub fn from_target(target: &Target, github_token: Option<&str>) -> Result<Self, Error> {
+        Ok(Self {
+            name: target.name.clone(),
+            sources: SourceChain::for_target(target, github_token, &SOURCES)?,
+        })
     }
-}
-
-#[async_trait]
-impl Collector for MySqlCollector {
-    fn name(&self) -> &str {
-        &self.name
-    }
-
-    async fn collect(&self) -> Result<DataFrame, Error> {
-        let tags = self.fetch_tags().await?;
 
+    fn parse_tags(&self, tags: Vec<SourceTag>) -> Vec<ProductCycle> {
         // Regex to match MySQL version tags like "mysql-8.0.42", "mysql-cluster-8.4.5", etc.
         // Also match Docker tags like "8.0.42", "5.7.44", "8.0.42-oracle", "9.3.0-oraclelinux9", etc.
         let re = Regex::new(r"(?:mysql(?:-cluster)?-)?(\d+\.\d+\.\d+)(?:-.*)?$").unwrap();
 
         let mut seen_versions = HashSet::new();
 
-        let cycles: Vec<ProductCycle> = tags
-            .into_iter()
+        tags.into_iter()
             .filter_map(|tag| {
                 // Extract version from tag name
                 if let Some(captures) = re.captures(&tag.name) {
@@ -144,19 +76,40 @@ impl Collector for MySqlCollector {
 
                         return Some(ProductCycle {
                             name: version_string,
-                            release_date: None,
+                            release_date: tag.date,
                             eol_date: None,
                             lts: false,
+                            eol_source: EolSource::Official,
+                            provenance: None,
+                            fallback: false,
                         });
                     }
                 }
                 None
             })
-            .collect();
+            .collect()
+    }
+}
 
-        if cycles.is_empty() {
-            return Err(Error::NotFound);
-        }
+#[async_trait]
+impl Collector for MySqlCollector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn source(&self) -> &str {
+        self.sources.primary()
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        self.sources.hosts()
+    }
+
+    async fn collect(&self) -> Result<DataFrame, Error> {
+        let cycles = self
+            .sources
+            .collect(&self.name, |tags| self.parse_tags(tags))
+            .await?;
 
         product_cycles_to_dataframe(cycles).map_err(Error::from)
     }
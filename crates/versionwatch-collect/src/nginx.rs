@@ -1,14 +1,9 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::sources::github_tags;
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use polars::prelude::DataFrame;
-use serde::Deserialize;
 
-const NGINX_RELEASES_URL: &str = "https://api.github.com/repos/nginx/nginx/tags";
-
-#[derive(Debug, Deserialize)]
-struct GitHubTag {
-    name: String,
-}
+const NGINX_REPOSITORY: &str = "nginx/nginx";
 
 pub struct NginxCollector {
     name: String,
@@ -30,29 +25,17 @@ impl Collector for NginxCollector {
         &self.name
     }
 
-    async fn collect(&self) -> Result<DataFrame, Error> {
-        let client = reqwest::Client::new();
-
-        // Récupérer les tags depuis GitHub
-        let mut request = client
-            .get(NGINX_RELEASES_URL)
-            .header("User-Agent", "VersionWatch/1.0")
-            .header("Accept", "application/vnd.github.v3+json");
-
-        // Ajouter le token GitHub si disponible
-        if let Some(token) = &self.github_token {
-            request = request.bearer_auth(token);
-        }
+    fn source(&self) -> &str {
+        "github_tags"
+    }
 
-        let response = request
-            .send()
-            .await
-            .map_err(|e| Error::Other(anyhow::anyhow!("Failed to fetch Nginx releases: {e}")))?;
+    fn hosts(&self) -> Vec<&str> {
+        vec!["api.github.com"]
+    }
 
-        let tags: Vec<GitHubTag> = response
-            .json()
-            .await
-            .map_err(|e| Error::Other(anyhow::anyhow!("Failed to parse Nginx releases: {e}")))?;
+    async fn collect(&self) -> Result<DataFrame, Error> {
+        // Récupérer les tags depuis GitHub, datés par leurs releases
+        let tags = github_tags(NGINX_REPOSITORY, self.github_token.as_deref()).await?;
 
         // Convertir en ProductCycle
         let mut cycles = Vec::new();
@@ -66,9 +49,12 @@ impl Collector for NginxCollector {
             {
                 let cycle = ProductCycle {
                     name: version.to_string(),
-                    release_date: None,
+                    release_date: tag.date,
                     eol_date: None,
                     lts: false,
+                    eol_source: EolSource::Official,
+                    provenance: None,
+                    fallback: false,
                 };
                 cycles.push(cycle);
             }
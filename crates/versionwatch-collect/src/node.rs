@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use polars::prelude::DataFrame;
@@ -39,6 +39,14 @@ impl Collector for NodeCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "nodejs_dist"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["nodejs.org", "raw.githubusercontent.com"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let releases: Vec<NodeVersion> = reqwest::get(NODE_RELEASES_URL).await?.json().await?;
         let schedule: HashMap<String, NodeSchedule> =
@@ -61,6 +69,9 @@ impl Collector for NodeCollector {
                     release_date: Some(v.date),
                     eol_date,
                     lts: v.lts.is_string(),
+                    eol_source: EolSource::Official,
+                    provenance: None,
+                    fallback: false,
                 }
             })
             .collect();
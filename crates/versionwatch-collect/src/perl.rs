@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use polars::prelude::DataFrame;
 use serde::Deserialize;
@@ -39,6 +39,14 @@ impl Collector for PerlCollector {
         "perl"
     }
 
+    fn source(&self) -> &str {
+        "metacpan"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["fastapi.metacpan.org"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let response: MetaCpanSearchResponse =
             reqwest::get(PERL_RELEASES_URL).await?.json().await?;
@@ -81,6 +89,9 @@ impl Collector for PerlCollector {
                 release_date,
                 eol_date: None, // Perl doesn't have official EOL dates
                 lts: false,     // Perl doesn't have LTS versions
+                eol_source: EolSource::Official,
+                provenance: None,
+                fallback: false,
             })
             .collect();
 
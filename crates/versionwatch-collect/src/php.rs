@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::NaiveDate;
@@ -100,6 +100,14 @@ impl Collector for PhpCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "php_releases"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["www.php.net"]
+    }
+
     #[instrument(err, skip(self))]
     async fn collect(&self) -> Result<DataFrame, Error> {
         let eol_dates = get_eol_dates().await?;
@@ -115,6 +123,9 @@ impl Collector for PhpCollector {
                     release_date: Some(v.date),
                     eol_date: eol_dates.get(&major_version).cloned(),
                     lts: false,
+                    eol_source: EolSource::Official,
+                    provenance: None,
+                    fallback: false,
                 }
             })
             .collect();
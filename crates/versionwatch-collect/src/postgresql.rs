@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use polars::prelude::DataFrame;
@@ -25,6 +25,14 @@ impl Collector for PostgresqlCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "postgresql_versioning"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["www.postgresql.org"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let response = reqwest::get(POSTGRESQL_VERSIONING_URL)
             .await?
@@ -55,6 +63,9 @@ impl Collector for PostgresqlCollector {
                         release_date,
                         eol_date,
                         lts: false, // PostgreSQL doesn't have an official LTS concept in the same way other projects do
+                        eol_source: EolSource::Official,
+                        provenance: None,
+                        fallback: false,
                     });
                 }
             }
@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use polars::prelude::DataFrame;
 use serde::Deserialize;
@@ -30,6 +30,14 @@ impl Collector for PythonCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "github_tags"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["api.github.com"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let client = reqwest::Client::new();
 
@@ -67,6 +75,9 @@ impl Collector for PythonCollector {
                     release_date: None,
                     eol_date: None,
                     lts: false,
+                    eol_source: EolSource::Official,
+                    provenance: None,
+                    fallback: false,
                 };
                 cycles.push(cycle);
             }
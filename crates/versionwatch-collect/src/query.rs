@@ -0,0 +1,120 @@
+//! Read-only SQL over the collected data, using Polars SQL.
+
+use crate::Error;
+use crate::bundle::{self, Dataset};
+use chrono::NaiveDate;
+use polars::prelude::*;
+use polars::sql::SQLContext;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Tables queries can read from, as written by `bundle::write_bundle`.
+pub const TABLES: &[&str] = &["products", "cycles", "releases"];
+
+/// Rows returned by a query.
+#[derive(Serialize, Debug, Clone)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// Whether rows past the limit were left out
+    pub truncated: bool,
+}
+
+/// Builds the queryable tables from collected rows.
+pub fn dataset_tables(
+    dataset: &Dataset,
+    today: NaiveDate,
+) -> Result<Vec<(&'static str, DataFrame)>, Error> {
+    Ok(vec![
+        ("products", bundle::products_frame(dataset)?),
+        ("cycles", bundle::cycles_frame(dataset, today)?),
+        ("releases", bundle::releases_frame(dataset)?),
+    ])
+}
+
+/// Reads the queryable tables of an exported bundle as they were written.
+pub fn bundle_tables(dir: &Path) -> Result<Vec<(&'static str, DataFrame)>, Error> {
+    let manifest = bundle::read_manifest(dir)?;
+    TABLES
+        .iter()
+        .map(|table| Ok((*table, bundle::read_table(dir, &manifest, table)?)))
+        .collect()
+}
+
+/// Runs a single `SELECT` statement against the tables, returning at most
+/// `limit` rows.
+pub fn run(
+    tables: Vec<(&str, DataFrame)>,
+    sql: &str,
+    limit: Option<usize>,
+) -> Result<QueryResult, Error> {
+    let statement = sql.trim().trim_end_matches(';');
+    let keyword = statement
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    if !matches!(keyword.as_str(), "SELECT" | "WITH") || statement.contains(';') {
+        return Err(Error::Other(anyhow::anyhow!(
+            "only a single SELECT statement is allowed"
+        )));
+    }
+
+    let mut context = SQLContext::new();
+    for (name, frame) in tables {
+        context.register(name, frame.lazy());
+    }
+    let mut lazy = context.execute(statement)?;
+    if let Some(limit) = limit {
+        // One extra row tells whether the result was cut.
+        lazy = lazy.limit(limit as IdxSize + 1);
+    }
+    let frame = lazy.collect()?;
+
+    let columns: Vec<String> = frame
+        .get_column_names()
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let height = limit.map_or(frame.height(), |l| frame.height().min(l));
+    let mut rows = Vec::with_capacity(height);
+    for i in 0..height {
+        let row = frame
+            .get_columns()
+            .iter()
+            .map(|column| column.get(i).map(json_value))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        rows.push(row);
+    }
+
+    Ok(QueryResult {
+        columns,
+        truncated: height < frame.height(),
+        rows,
+    })
+}
+
+fn json_value(value: AnyValue) -> Value {
+    match value {
+        AnyValue::Null => Value::Null,
+        AnyValue::Boolean(b) => Value::Bool(b),
+        AnyValue::String(s) => Value::String(s.to_string()),
+        AnyValue::StringOwned(s) => Value::String(s.to_string()),
+        AnyValue::UInt8(n) => n.into(),
+        AnyValue::UInt16(n) => n.into(),
+        AnyValue::UInt32(n) => n.into(),
+        AnyValue::UInt64(n) => n.into(),
+        AnyValue::Int8(n) => n.into(),
+        AnyValue::Int16(n) => n.into(),
+        AnyValue::Int32(n) => n.into(),
+        AnyValue::Int64(n) => n.into(),
+        AnyValue::Float32(n) => n.into(),
+        AnyValue::Float64(n) => n.into(),
+        AnyValue::Date(days) => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+            Value::String((epoch + chrono::Duration::days(days as i64)).to_string())
+        }
+        other => Value::String(other.to_string()),
+    }
+}
@@ -0,0 +1,377 @@
+//! Merges the rows several sources return for a product into one set of rows,
+//! recording where each row and field comes from.
+
+use crate::Error;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use versionwatch_core::domain::lifecycle::version_parts;
+use versionwatch_core::domain::product_cycle::ProductCycle;
+use versionwatch_core::domain::provenance::{Conflict, Provenance, Sourced, SourcedValue};
+
+/// Rows one source returned, and when.
+#[derive(Debug, Clone)]
+pub struct SourceRows {
+    pub source: String,
+    pub fetched_at: DateTime<Utc>,
+    pub rows: Vec<ProductCycle>,
+}
+
+impl SourceRows {
+    /// Rows fetched just now.
+    pub fn new(source: &str, rows: Vec<ProductCycle>) -> Self {
+        Self {
+            source: source.to_string(),
+            fetched_at: Utc::now(),
+            rows,
+        }
+    }
+
+    fn sourced(&self) -> Sourced {
+        Sourced {
+            source: self.source.clone(),
+            fetched_at: self.fetched_at,
+        }
+    }
+}
+
+/// The key rows of different sources are matched on: numeric components
+/// without a "v" prefix or trailing zeros, so "v5.9", "5.9" and "5.9.0" match,
+/// plus any pre-release suffix.
+pub fn normalize_version(name: &str) -> String {
+    let trimmed = name.trim().trim_start_matches('v');
+    let mut parts = version_parts(trimmed);
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+    let suffix = trimmed
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.')
+        .to_ascii_lowercase();
+    let numeric = parts
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>()
+        .join(".");
+    format!("{numeric}{suffix}")
+}
+
+/// Merges the rows of several sources, given in priority order, by normalized
+/// version. Each field takes the value of the first source that has one;
+/// other sources that disagree are recorded as conflicts.
+pub fn reconcile(results: &[SourceRows]) -> Vec<ProductCycle> {
+    let mut merged: Vec<ProductCycle> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for result in results {
+        let sourced = result.sourced();
+        for row in &result.rows {
+            let key = normalize_version(&row.name);
+            let Some(&i) = index.get(&key) else {
+                let mut provenance = Provenance::single(&result.source, result.fetched_at);
+                for field in present_fields(row) {
+                    provenance.fields.insert(field.to_string(), sourced.clone());
+                }
+                let mut row = row.clone();
+                row.provenance = Some(provenance);
+                index.insert(key, merged.len());
+                merged.push(row);
+                continue;
+            };
+
+            let existing = &mut merged[i];
+            let mut provenance = existing
+                .provenance
+                .take()
+                .unwrap_or_else(|| Provenance::single(&result.source, result.fetched_at));
+            if !provenance
+                .sources
+                .iter()
+                .any(|s| s.source == sourced.source)
+            {
+                provenance.sources.push(sourced.clone());
+            }
+            merge_field(
+                "release_date",
+                &mut existing.release_date,
+                row.release_date,
+                &sourced,
+                &mut provenance,
+            );
+            if existing.eol_date.is_none() && row.eol_date.is_some() {
+                existing.eol_source = row.eol_source;
+            }
+            merge_field(
+                "eol_date",
+                &mut existing.eol_date,
+                row.eol_date,
+                &sourced,
+                &mut provenance,
+            );
+            if !existing.lts && row.lts {
+                existing.lts = true;
+                provenance.fields.insert("lts".to_string(), sourced.clone());
+            }
+            existing.provenance = Some(provenance);
+        }
+    }
+    merged
+}
+
+/// Reconciles the outcome of querying several sources, in priority order.
+/// Failed sources are reported and left out; it is an error only when every
+/// source failed or returned nothing.
+pub fn merge_sources(
+    product: &str,
+    outcomes: Vec<(&str, Result<Vec<ProductCycle>, Error>)>,
+) -> Result<Vec<ProductCycle>, Error> {
+    let mut results = Vec::new();
+    let mut last_error = Error::NotFound;
+    for (source, outcome) in outcomes {
+        match outcome {
+            Ok(rows) if !rows.is_empty() => results.push(SourceRows::new(source, rows)),
+            Ok(_) => eprintln!("⚠️  {product}: {source} returned no versions"),
+            Err(e) => {
+                eprintln!("⚠️  {product}: {source} failed: {e}");
+                last_error = e;
+            }
+        }
+    }
+    if results.is_empty() {
+        return Err(last_error);
+    }
+
+    let rows = reconcile(&results);
+    for row in &rows {
+        for conflict in row.provenance.iter().flat_map(|p| &p.conflicts) {
+            let values: Vec<String> = conflict
+                .values
+                .iter()
+                .map(|v| format!("{}={}", v.source, v.value))
+                .collect();
+            eprintln!(
+                "⚠️  {product} {}: sources disagree on {}: {}",
+                row.name,
+                conflict.field,
+                values.join(", ")
+            );
+        }
+    }
+    Ok(rows)
+}
+
+fn present_fields(row: &ProductCycle) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if row.release_date.is_some() {
+        fields.push("release_date");
+    }
+    if row.eol_date.is_some() {
+        fields.push("eol_date");
+    }
+    if row.lts {
+        fields.push("lts");
+    }
+    fields
+}
+
+fn merge_field<T: PartialEq + ToString + Copy>(
+    field: &str,
+    current: &mut Option<T>,
+    incoming: Option<T>,
+    sourced: &Sourced,
+    provenance: &mut Provenance,
+) {
+    let Some(value) = incoming else {
+        return;
+    };
+    let Some(kept) = *current else {
+        *current = Some(value);
+        provenance.fields.insert(field.to_string(), sourced.clone());
+        return;
+    };
+    if kept == value {
+        return;
+    }
+
+    let kept_source = provenance
+        .fields
+        .get(field)
+        .map(|s| s.source.clone())
+        .unwrap_or_default();
+    let entry = SourcedValue {
+        source: sourced.source.clone(),
+        value: value.to_string(),
+    };
+    match provenance.conflicts.iter_mut().find(|c| c.field == field) {
+        Some(conflict) => conflict.values.push(entry),
+        None => provenance.conflicts.push(Conflict {
+            field: field.to_string(),
+            values: vec![
+                SourcedValue {
+                    source: kept_source,
+                    value: kept.to_string(),
+                },
+                entry,
+            ],
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use versionwatch_core::domain::product_cycle::EolSource;
+
+    fn date(s: &str) -> Option<NaiveDate> {
+        Some(s.parse().unwrap())
+    }
+
+    fn row(
+        name: &str,
+        release_date: Option<NaiveDate>,
+        eol_date: Option<NaiveDate>,
+    ) -> ProductCycle {
+        ProductCycle {
+            name: name.to_string(),
+            release_date,
+            eol_date,
+            lts: false,
+            eol_source: EolSource::Official,
+            provenance: None,
+            fallback: false,
+        }
+    }
+
+    fn source(name: &str, rows: Vec<ProductCycle>) -> SourceRows {
+        SourceRows {
+            source: name.to_string(),
+            fetched_at: "2026-10-18T12:00:00Z".parse().unwrap(),
+            rows,
+        }
+    }
+
+    #[test]
+    fn normalize_version_matches_equivalent_spellings() {
+        assert_eq!(normalize_version("v5.9"), "5.9");
+        assert_eq!(normalize_version("5.9.0"), "5.9");
+        assert_eq!(normalize_version(" 5.9 "), "5.9");
+        assert_eq!(normalize_version("8.0.0"), "8");
+        assert_eq!(normalize_version("10"), "10");
+        assert_eq!(normalize_version("5.10"), "5.10");
+    }
+
+    #[test]
+    fn normalize_version_keeps_prerelease_suffixes() {
+        assert_eq!(normalize_version("5.9.0-rc1"), "5.9-rc1");
+        assert_eq!(normalize_version("v5.9-RC1"), "5.9-rc1");
+        assert_ne!(normalize_version("5.9-rc1"), normalize_version("5.9"));
+    }
+
+    #[test]
+    fn reconcile_merges_rows_by_normalized_version() {
+        let rows = reconcile(&[
+            source("github_tags", vec![row("v5.9", date("2023-09-18"), None)]),
+            source(
+                "dockerhub",
+                vec![row("5.9.0", None, None), row("5.8", None, None)],
+            ),
+        ]);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].name, "v5.9");
+        assert_eq!(rows[1].name, "5.8");
+
+        let provenance = rows[0].provenance.as_ref().unwrap();
+        let sources: Vec<&str> = provenance
+            .sources
+            .iter()
+            .map(|s| s.source.as_str())
+            .collect();
+        assert_eq!(sources, ["github_tags", "dockerhub"]);
+        assert_eq!(provenance.fields["release_date"].source, "github_tags");
+        assert!(provenance.conflicts.is_empty());
+    }
+
+    #[test]
+    fn reconcile_fills_fields_from_lower_priority_sources() {
+        let rows = reconcile(&[
+            source("dockerhub", vec![row("8.0", None, None)]),
+            source("github_tags", vec![row("8.0.0", date("2018-04-19"), None)]),
+        ]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].release_date, date("2018-04-19"));
+        let provenance = rows[0].provenance.as_ref().unwrap();
+        assert_eq!(provenance.fields["release_date"].source, "github_tags");
+    }
+
+    #[test]
+    fn reconcile_keeps_the_first_value_and_records_conflicts() {
+        let rows = reconcile(&[
+            source(
+                "a",
+                vec![row("1.2", date("2024-01-01"), date("2025-01-01"))],
+            ),
+            source(
+                "b",
+                vec![row("1.2", date("2024-01-02"), date("2025-01-01"))],
+            ),
+            source("c", vec![row("1.2.0", date("2024-01-03"), None)]),
+        ]);
+        assert_eq!(rows[0].release_date, date("2024-01-01"));
+        assert_eq!(rows[0].eol_date, date("2025-01-01"));
+
+        let conflicts = &rows[0].provenance.as_ref().unwrap().conflicts;
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].field, "release_date");
+        let values: Vec<(&str, &str)> = conflicts[0]
+            .values
+            .iter()
+            .map(|v| (v.source.as_str(), v.value.as_str()))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("a", "2024-01-01"),
+                ("b", "2024-01-02"),
+                ("c", "2024-01-03")
+            ]
+        );
+    }
+
+    #[test]
+    fn reconcile_takes_the_eol_source_with_the_eol_date() {
+        let mut inferred = row("3.1", None, date("2027-03-31"));
+        inferred.eol_source = EolSource::Inferred;
+        inferred.lts = true;
+        let rows = reconcile(&[
+            source("a", vec![row("3.1", date("2024-03-31"), None)]),
+            source("b", vec![inferred]),
+        ]);
+        assert_eq!(rows[0].eol_date, date("2027-03-31"));
+        assert_eq!(rows[0].eol_source, EolSource::Inferred);
+        assert!(rows[0].lts);
+        let fields = &rows[0].provenance.as_ref().unwrap().fields;
+        assert_eq!(fields["eol_date"].source, "b");
+        assert_eq!(fields["lts"].source, "b");
+    }
+
+    #[test]
+    fn merge_sources_skips_failed_sources_and_fails_when_all_do() {
+        let rows = merge_sources(
+            "mysql",
+            vec![
+                ("github_tags", Err(Error::NotFound)),
+                ("dockerhub", Ok(vec![row("8.4", None, None)])),
+            ],
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+
+        let all_failed = merge_sources(
+            "mysql",
+            vec![
+                ("github_tags", Err(Error::NotFound)),
+                ("dockerhub", Ok(Vec::new())),
+            ],
+        );
+        assert!(all_failed.is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use crate::Error;
+use chrono::{DateTime, NaiveDate};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use versionwatch_core::domain::dependency::{Ecosystem, PackageRelease};
+
+const CRATES_IO_URL: &str = "https://crates.io/api/v1/crates";
+const NPM_REGISTRY_URL: &str = "https://registry.npmjs.org";
+const PYPI_URL: &str = "https://pypi.org/pypi";
+const RUBYGEMS_URL: &str = "https://rubygems.org/api/v1/versions";
+const PACKAGIST_URL: &str = "https://repo.packagist.org/p2";
+const GO_PROXY_URL: &str = "https://proxy.golang.org";
+
+/// Releases of a package as listed by its registry.
+#[derive(Debug, Clone, Default)]
+pub struct PackageReleases {
+    /// The registry's own notion of the latest release, when it has one
+    pub latest: Option<String>,
+    pub releases: Vec<PackageRelease>,
+}
+
+/// Looks up package releases in the public registry of each ecosystem.
+pub struct RegistryClient {
+    client: reqwest::Client,
+}
+
+impl Default for RegistryClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegistryClient {
+    pub fn new() -> Self {
+        Self {
+            // crates.io rejects requests without a user agent.
+            client: reqwest::Client::builder()
+                .user_agent("VersionWatch/1.0")
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Fetches every published release of a package.
+    pub async fn releases(
+        &self,
+        ecosystem: Ecosystem,
+        name: &str,
+    ) -> Result<PackageReleases, Error> {
+        match ecosystem {
+            Ecosystem::Cargo => self.crates_io(name).await,
+            Ecosystem::Npm => self.npm(name).await,
+            Ecosystem::PyPi => self.pypi(name).await,
+            Ecosystem::RubyGems => self.rubygems(name).await,
+            Ecosystem::Packagist => self.packagist(name).await,
+            Ecosystem::Go => self.go_proxy(name).await,
+        }
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<T, Error> {
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    async fn crates_io(&self, name: &str) -> Result<PackageReleases, Error> {
+        #[derive(Deserialize)]
+        struct Response {
+            versions: Vec<CrateVersion>,
+        }
+        #[derive(Deserialize)]
+        struct CrateVersion {
+            num: String,
+            created_at: String,
+            yanked: bool,
+        }
+
+        let response: Response = self.get_json(&format!("{CRATES_IO_URL}/{name}")).await?;
+        Ok(PackageReleases {
+            latest: None,
+            releases: response
+                .versions
+                .into_iter()
+                .filter(|v| !v.yanked)
+                .map(|v| release(v.num, &v.created_at))
+                .collect(),
+        })
+    }
+
+    async fn npm(&self, name: &str) -> Result<PackageReleases, Error> {
+        #[derive(Deserialize)]
+        struct Response {
+            #[serde(rename = "dist-tags", default)]
+            dist_tags: HashMap<String, String>,
+            #[serde(default)]
+            time: HashMap<String, String>,
+        }
+
+        // Scoped names keep their "@" but encode the slash.
+        let response: Response = self
+            .get_json(&format!("{NPM_REGISTRY_URL}/{}", name.replace('/', "%2F")))
+            .await?;
+        Ok(PackageReleases {
+            latest: response.dist_tags.get("latest").cloned(),
+            releases: response
+                .time
+                .into_iter()
+                .filter(|(version, _)| version != "created" && version != "modified")
+                .map(|(version, time)| release(version, &time))
+                .collect(),
+        })
+    }
+
+    async fn pypi(&self, name: &str) -> Result<PackageReleases, Error> {
+        #[derive(Deserialize)]
+        struct Response {
+            info: Info,
+            releases: HashMap<String, Vec<File>>,
+        }
+        #[derive(Deserialize)]
+        struct Info {
+            version: String,
+        }
+        #[derive(Deserialize)]
+        struct File {
+            upload_time: String,
+            #[serde(default)]
+            yanked: bool,
+        }
+
+        let response: Response = self.get_json(&format!("{PYPI_URL}/{name}/json")).await?;
+        Ok(PackageReleases {
+            latest: Some(response.info.version),
+            releases: response
+                .releases
+                .into_iter()
+                .filter(|(_, files)| !files.is_empty() && files.iter().any(|f| !f.yanked))
+                .map(|(version, files)| {
+                    let published = files.iter().map(|f| f.upload_time.as_str()).min();
+                    release(version, published.unwrap_or_default())
+                })
+                .collect(),
+        })
+    }
+
+    async fn rubygems(&self, name: &str) -> Result<PackageReleases, Error> {
+        #[derive(Deserialize)]
+        struct GemVersion {
+            number: String,
+            created_at: String,
+            #[serde(default)]
+            platform: String,
+        }
+
+        let versions: Vec<GemVersion> = self
+            .get_json(&format!("{RUBYGEMS_URL}/{name}.json"))
+            .await?;
+        Ok(PackageReleases {
+            latest: None,
+            releases: versions
+                .into_iter()
+                .filter(|v| v.platform.is_empty() || v.platform == "ruby")
+                .map(|v| release(v.number, &v.created_at))
+                .collect(),
+        })
+    }
+
+    async fn packagist(&self, name: &str) -> Result<PackageReleases, Error> {
+        let response: Value = self
+            .get_json(&format!("{PACKAGIST_URL}/{name}.json"))
+            .await?;
+        let versions = response
+            .get("packages")
+            .and_then(|p| p.get(name))
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(PackageReleases {
+            latest: None,
+            releases: versions
+                .iter()
+                .filter_map(|v| {
+                    let version = v.get("version")?.as_str()?;
+                    let time = v.get("time").and_then(Value::as_str).unwrap_or_default();
+                    Some(release(version.to_string(), time))
+                })
+                .collect(),
+        })
+    }
+
+    async fn go_proxy(&self, module: &str) -> Result<PackageReleases, Error> {
+        #[derive(Deserialize)]
+        struct Info {
+            #[serde(rename = "Version")]
+            version: String,
+            #[serde(rename = "Time")]
+            time: String,
+        }
+
+        let module = escape_module(module);
+        let latest: Info = self
+            .get_json(&format!("{GO_PROXY_URL}/{module}/@latest"))
+            .await?;
+        let list = self
+            .client
+            .get(format!("{GO_PROXY_URL}/{module}/@v/list"))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        // The list carries no dates; the latest release's date comes from @latest.
+        let mut releases: Vec<PackageRelease> = list
+            .lines()
+            .filter(|v| !v.is_empty() && *v != latest.version)
+            .map(|v| PackageRelease {
+                version: v.to_string(),
+                published: None,
+            })
+            .collect();
+        releases.push(release(latest.version.clone(), &latest.time));
+
+        Ok(PackageReleases {
+            latest: Some(latest.version),
+            releases,
+        })
+    }
+
+    /// Looks up when a single version was published, for registries whose
+    /// release lists carry no dates (the Go proxy).
+    pub async fn published(
+        &self,
+        ecosystem: Ecosystem,
+        name: &str,
+        version: &str,
+    ) -> Result<Option<NaiveDate>, Error> {
+        if ecosystem != Ecosystem::Go {
+            return Ok(None);
+        }
+        #[derive(Deserialize)]
+        struct Info {
+            #[serde(rename = "Time")]
+            time: String,
+        }
+        let info: Info = self
+            .get_json(&format!(
+                "{GO_PROXY_URL}/{}/@v/{version}.info",
+                escape_module(name)
+            ))
+            .await?;
+        Ok(parse_date(&info.time))
+    }
+}
+
+/// The Go proxy escapes upper-case letters as "!" followed by the lower-case letter.
+fn escape_module(module: &str) -> String {
+    module
+        .chars()
+        .flat_map(|c| match c.is_ascii_uppercase() {
+            true => vec!['!', c.to_ascii_lowercase()],
+            false => vec![c],
+        })
+        .collect()
+}
+
+fn release(version: String, published: &str) -> PackageRelease {
+    PackageRelease {
+        version,
+        published: parse_date(published),
+    }
+}
+
+/// Parses RFC 3339 timestamps as well as PyPI's zone-less "2024-01-02T03:04:05".
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|d| d.date_naive())
+        .ok()
+        .or_else(|| value.get(..10)?.parse().ok())
+}
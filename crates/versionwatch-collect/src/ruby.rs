@@ -1,5 +1,6 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use polars::prelude::DataFrame;
 use serde::Deserialize;
 
@@ -9,6 +10,7 @@ const RUBY_RELEASES_URL: &str = "https://api.github.com/repos/ruby/ruby/releases
 struct GitHubRelease {
     tag_name: String,
     prerelease: bool,
+    published_at: Option<DateTime<Utc>>,
 }
 
 pub struct RubyCollector {
@@ -31,6 +33,14 @@ impl Collector for RubyCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "github_releases"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["api.github.com"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let client = reqwest::Client::new();
 
@@ -64,18 +74,23 @@ impl Collector for RubyCollector {
             }
 
             // Nettoyer le nom de version (enlever le préfixe 'v' si présent)
+            // Ruby tags separate components with underscores, e.g. "v3_3_0".
             let version = release
                 .tag_name
                 .strip_prefix('v')
-                .unwrap_or(&release.tag_name);
+                .unwrap_or(&release.tag_name)
+                .replace('_', ".");
 
             // Ne garder que les versions qui ressemblent à des versions Ruby (x.y.z)
             if version.chars().next().unwrap_or('0').is_ascii_digit() {
                 let cycle = ProductCycle {
                     name: version.to_string(),
-                    release_date: None,
+                    release_date: release.published_at.map(|d| d.date_naive()),
                     eol_date: None,
                     lts: false,
+                    eol_source: EolSource::Official,
+                    provenance: None,
+                    fallback: false,
                 };
                 cycles.push(cycle);
             }
@@ -1,4 +1,4 @@
-use crate::{Collector, Error, ProductCycle, product_cycles_to_dataframe};
+use crate::{Collector, EolSource, Error, ProductCycle, product_cycles_to_dataframe};
 use async_trait::async_trait;
 use polars::prelude::DataFrame;
 use serde::Deserialize;
@@ -31,6 +31,14 @@ impl Collector for RustCollector {
         &self.name
     }
 
+    fn source(&self) -> &str {
+        "github_releases"
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["api.github.com"]
+    }
+
     async fn collect(&self) -> Result<DataFrame, Error> {
         let client = reqwest::Client::new();
 
@@ -76,6 +84,9 @@ impl Collector for RustCollector {
                     release_date: None,
                     eol_date: None,
                     lts: false,
+                    eol_source: EolSource::Official,
+                    provenance: None,
+                    fallback: false,
                 };
                 cycles.push(cycle);
             }
@@ -0,0 +1,444 @@
+//! Release sources shared by collectors that read tags from more than one
+//! place, and the chains collectors run them in.
+
+use crate::Error;
+use crate::fallback::FallbackDataset;
+use crate::reconcile::merge_sources;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::future::join_all;
+use regex::Regex;
+use std::collections::HashMap;
+use versionwatch_config::{SourceConfig, SourceKind, SourceMode, Target};
+use versionwatch_core::domain::product_cycle::ProductCycle;
+
+const GITHUB_API_URL: &str = "https://api.github.com/repos";
+const DOCKER_HUB_URL: &str = "https://hub.docker.com/v2/repositories";
+
+/// A version tag and, when the source dates its tags, when it was published.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceTag {
+    pub name: String,
+    pub date: Option<NaiveDate>,
+}
+
+impl SourceTag {
+    pub fn undated(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            date: None,
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct Tag {
+    name: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct Release {
+    tag_name: String,
+    published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct DockerHubTag {
+    name: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct DockerHubResponse {
+    results: Vec<DockerHubTag>,
+}
+
+fn github_request(url: String, token: Option<&str>) -> reqwest::RequestBuilder {
+    let request = reqwest::Client::new()
+        .get(url)
+        .header("User-Agent", "versionwatch-collector")
+        .header("Accept", "application/vnd.github.v3+json");
+    match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+/// Lists the latest 100 tags of a GitHub repository, e.g. "mysql/mysql-server".
+/// The tags API does not date tags, so tags are dated from the publication
+/// date of the repository's latest 100 releases; tags without a release, and
+/// every tag when releases cannot be listed, are left undated.
+pub async fn github_tags(repository: &str, token: Option<&str>) -> Result<Vec<SourceTag>, Error> {
+    let url = format!("{GITHUB_API_URL}/{repository}/tags?per_page=100");
+    let response = github_request(url, token).send().await?;
+    match response.status() {
+        status if status.is_success() => {
+            let tags: Vec<Tag> = response.json().await?;
+            let published = github_release_dates(repository, token).await;
+            Ok(tags
+                .into_iter()
+                .map(|t| SourceTag {
+                    date: published.get(&t.name).copied(),
+                    name: t.name,
+                })
+                .collect())
+        }
+        reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS => {
+            Err(Error::RateLimited(format!("GitHub tags of {repository}")))
+        }
+        other => Err(Error::Other(anyhow::anyhow!(
+            "GitHub API returned unexpected status: {other}"
+        ))),
+    }
+}
+
+/// Publication dates of a GitHub repository's latest 100 releases, by tag.
+async fn github_release_dates(repository: &str, token: Option<&str>) -> HashMap<String, NaiveDate> {
+    let url = format!("{GITHUB_API_URL}/{repository}/releases?per_page=100");
+    let releases: Vec<Release> = match github_request(url, token).send().await {
+        Ok(response) if response.status().is_success() => response.json().await.unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    releases
+        .into_iter()
+        .filter_map(|r| Some((r.tag_name, r.published_at?.date_naive())))
+        .collect()
+}
+
+/// Lists the latest 500 tags of an image on Docker Hub, e.g. "library/mysql".
+/// Tags are left undated: Docker Hub only knows when a tag was last pushed,
+/// which changes with every image rebuild.
+pub async fn dockerhub_tags(image: &str) -> Result<Vec<SourceTag>, Error> {
+    let url = format!("{DOCKER_HUB_URL}/{image}/tags/?page_size=500");
+    let response = reqwest::Client::new()
+        .get(url)
+        .header("User-Agent", "versionwatch-collector")
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(Error::Other(anyhow::anyhow!(
+            "Docker Hub API returned status: {}",
+            response.status()
+        )));
+    }
+
+    let response: DockerHubResponse = response.json().await?;
+    Ok(response
+        .results
+        .into_iter()
+        .map(|t| SourceTag::undated(&t.name))
+        .collect())
+}
+
+/// A place a collector reads version tags from.
+#[async_trait]
+pub trait TagSource: std::fmt::Debug + Send + Sync {
+    /// Source name recorded in provenance, e.g. "github_tags"
+    fn name(&self) -> &str;
+
+    /// Host the source fetches from, if it fetches at all
+    fn host(&self) -> Option<&str> {
+        None
+    }
+
+    /// When the source serves a curated fallback dataset rather than querying
+    /// a live service, the date the dataset was curated. Fallback sources are
+    /// only used when every live source fails.
+    fn curated(&self) -> Option<NaiveDate> {
+        None
+    }
+
+    async fn tags(&self) -> Result<Vec<SourceTag>, Error>;
+}
+
+#[derive(Debug)]
+pub struct GitHubTags {
+    pub repository: String,
+    pub token: Option<String>,
+}
+
+#[async_trait]
+impl TagSource for GitHubTags {
+    fn name(&self) -> &str {
+        SourceKind::GithubTags.label()
+    }
+
+    fn host(&self) -> Option<&str> {
+        Some("api.github.com")
+    }
+
+    async fn tags(&self) -> Result<Vec<SourceTag>, Error> {
+        github_tags(&self.repository, self.token.as_deref()).await
+    }
+}
+
+#[derive(Debug)]
+pub struct DockerHubTags {
+    pub image: String,
+}
+
+#[async_trait]
+impl TagSource for DockerHubTags {
+    fn name(&self) -> &str {
+        SourceKind::Dockerhub.label()
+    }
+
+    fn host(&self) -> Option<&str> {
+        Some("hub.docker.com")
+    }
+
+    async fn tags(&self) -> Result<Vec<SourceTag>, Error> {
+        dockerhub_tags(&self.image).await
+    }
+}
+
+/// Known versions from a curated fallback dataset.
+#[derive(Debug)]
+pub struct StaticVersions {
+    pub dataset: FallbackDataset,
+}
+
+#[async_trait]
+impl TagSource for StaticVersions {
+    fn name(&self) -> &str {
+        SourceKind::Static.label()
+    }
+
+    fn curated(&self) -> Option<NaiveDate> {
+        Some(self.dataset.curated)
+    }
+
+    async fn tags(&self) -> Result<Vec<SourceTag>, Error> {
+        Ok(self
+            .dataset
+            .versions
+            .iter()
+            .map(|v| SourceTag::undated(v))
+            .collect())
+    }
+}
+
+/// What a collector reads from when its target does not configure sources.
+#[derive(Debug, Clone, Copy)]
+pub struct SourceDefaults {
+    pub repository: &'static str,
+    pub image: &'static str,
+    /// Built-in fallback dataset of a static source, if the product has one
+    pub fallback: Option<&'static str>,
+    /// Sources in priority order
+    pub chain: &'static [SourceKind],
+}
+
+#[derive(Debug)]
+struct Link {
+    source: Box<dyn TagSource>,
+    tag_regex: Option<Regex>,
+}
+
+impl Link {
+    /// Tags of the source, narrowed by its tag regex.
+    async fn tags(&self) -> Result<Vec<SourceTag>, Error> {
+        let tags = self.source.tags().await?;
+        let Some(re) = &self.tag_regex else {
+            return Ok(tags);
+        };
+        Ok(tags
+            .iter()
+            .filter_map(|tag| {
+                let captures = re.captures(&tag.name)?;
+                let version = captures.get(1).or_else(|| captures.get(0))?;
+                Some(SourceTag {
+                    name: version.as_str().to_string(),
+                    date: tag.date,
+                })
+            })
+            .collect())
+    }
+}
+
+/// An ordered chain of sources a tag-based collector reads versions from.
+#[derive(Debug)]
+pub struct SourceChain {
+    links: Vec<Link>,
+    mode: SourceMode,
+}
+
+impl SourceChain {
+    /// The collector's default chain, merged.
+    pub fn from_defaults(defaults: &SourceDefaults, github_token: Option<&str>) -> Self {
+        // Built-in datasets always load, and only products with one list a
+        // static source in their defaults.
+        let links = defaults
+            .chain
+            .iter()
+            .filter_map(|&kind| {
+                let source = adapter(SourceConfig::from(kind), None, github_token, defaults);
+                Some(Link {
+                    source: source.ok()?,
+                    tag_regex: None,
+                })
+            })
+            .collect();
+        Self {
+            links,
+            mode: SourceMode::Merge,
+        }
+    }
+
+    /// The chain a target configures, or the collector's defaults when it
+    /// configures none. Options a source leaves out fall back to the target's
+    /// repository and the collector's defaults.
+    pub fn for_target(
+        target: &Target,
+        github_token: Option<&str>,
+        defaults: &SourceDefaults,
+    ) -> Result<Self, Error> {
+        if target.sources.is_empty() {
+            let mut chain = Self::from_defaults(defaults, github_token);
+            chain.mode = target.source_mode;
+            return Ok(chain);
+        }
+
+        let links = target
+            .sources
+            .iter()
+            .map(|config| {
+                let tag_regex = config
+                    .tag_regex
+                    .as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .map_err(|e| anyhow::anyhow!("invalid tag_regex for {}: {e}", target.name))?;
+                let source = adapter(
+                    config.clone(),
+                    target.repository.as_deref(),
+                    github_token,
+                    defaults,
+                )?;
+                Ok(Link { source, tag_regex })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            links,
+            mode: target.source_mode,
+        })
+    }
+
+    /// Name of the chain's highest-priority source.
+    pub fn primary(&self) -> &str {
+        self.links
+            .first()
+            .map(|l| l.source.name())
+            .unwrap_or_default()
+    }
+
+    /// Hosts the chain's sources fetch from.
+    pub fn hosts(&self) -> Vec<&str> {
+        let mut hosts: Vec<&str> = self.links.iter().filter_map(|l| l.source.host()).collect();
+        hosts.dedup();
+        hosts
+    }
+
+    /// Collects the product's rows, parsing each source's tags with `parse`.
+    /// Parsers date rows from their tags so sources can be reconciled on
+    /// release dates.
+    ///
+    /// In merge mode every live source is queried at once and their rows are
+    /// reconciled in priority order; static sources serve only when all live
+    /// sources fail. In fallback mode sources are tried in order and the first
+    /// one that yields rows is used. Rows served from a static source are
+    /// flagged as fallback data.
+    pub async fn collect(
+        &self,
+        product: &str,
+        parse: impl Fn(Vec<SourceTag>) -> Vec<ProductCycle>,
+    ) -> Result<Vec<ProductCycle>, Error> {
+        match self.mode {
+            SourceMode::Merge => {
+                let (live, fixed): (Vec<&Link>, Vec<&Link>) = self
+                    .links
+                    .iter()
+                    .partition(|l| l.source.curated().is_none());
+                let mut last_error = Error::NotFound;
+                for tier in [live, fixed] {
+                    if tier.is_empty() {
+                        continue;
+                    }
+                    let outcomes = join_all(tier.iter().map(|l| l.tags())).await;
+                    let outcomes = tier
+                        .iter()
+                        .zip(outcomes)
+                        .map(|(l, tags)| (l.source.name(), tags.map(&parse)))
+                        .collect();
+                    let curated = tier.iter().filter_map(|l| l.source.curated()).min();
+                    match merge_sources(product, outcomes) {
+                        Ok(rows) => return Ok(mark_fallback(rows, curated)),
+                        Err(e) => last_error = e,
+                    }
+                }
+                Err(last_error)
+            }
+            SourceMode::Fallback => {
+                let mut last_error = Error::NotFound;
+                for link in &self.links {
+                    let outcome = vec![(link.source.name(), link.tags().await.map(&parse))];
+                    match merge_sources(product, outcome) {
+                        Ok(rows) => return Ok(mark_fallback(rows, link.source.curated())),
+                        Err(e) => last_error = e,
+                    }
+                }
+                Err(last_error)
+            }
+        }
+    }
+}
+
+/// Flags rows served from a fallback dataset curated on `curated`, dating
+/// their static source to when the dataset was curated.
+fn mark_fallback(mut rows: Vec<ProductCycle>, curated: Option<NaiveDate>) -> Vec<ProductCycle> {
+    let Some(curated) = curated else {
+        return rows;
+    };
+    let curated_at = curated.and_time(chrono::NaiveTime::MIN).and_utc();
+    for row in &mut rows {
+        row.fallback = true;
+        let sources = row.provenance.iter_mut().flat_map(|p| &mut p.sources);
+        for sourced in sources.filter(|s| s.source == SourceKind::Static.label()) {
+            sourced.fetched_at = curated_at;
+        }
+    }
+    rows
+}
+
+fn adapter(
+    config: SourceConfig,
+    repository: Option<&str>,
+    github_token: Option<&str>,
+    defaults: &SourceDefaults,
+) -> Result<Box<dyn TagSource>, Error> {
+    Ok(match config.kind {
+        SourceKind::GithubTags => Box::new(GitHubTags {
+            repository: config
+                .repository
+                .or_else(|| repository.map(str::to_string))
+                .unwrap_or_else(|| defaults.repository.to_string()),
+            token: github_token.map(str::to_string),
+        }),
+        SourceKind::Dockerhub => Box::new(DockerHubTags {
+            image: config.image.unwrap_or_else(|| defaults.image.to_string()),
+        }),
+        SourceKind::Static => {
+            let dataset = match &config.file {
+                Some(file) => FallbackDataset::load(file).map_err(|e| {
+                    anyhow::anyhow!("could not read fallback dataset {}: {e}", file.display())
+                })?,
+                None => defaults
+                    .fallback
+                    .and_then(FallbackDataset::builtin)
+                    .ok_or_else(|| anyhow::anyhow!("no built-in fallback dataset, set a file"))?,
+            };
+            Box::new(StaticVersions { dataset })
+        }
+    })
+}
@@ -0,0 +1,297 @@
+//! Checks collector output before it is stored, setting aside the rows that
+//! fail so a broken source cannot write garbage.
+
+use crate::{Error, dataframe_to_product_cycles};
+use chrono::{NaiveDate, Utc};
+use polars::prelude::{DataFrame, DataType};
+use std::collections::HashSet;
+use versionwatch_config::Validation;
+use versionwatch_core::domain::lifecycle::version_parts;
+use versionwatch_core::domain::product_cycle::ProductCycle;
+use versionwatch_core::domain::quarantine::{QuarantineReason, QuarantinedRow};
+
+/// Columns every collector frame has, with their types.
+const REQUIRED_COLUMNS: [(&str, DataType); 4] = [
+    ("name", DataType::String),
+    ("release_date", DataType::Int32),
+    ("eol_date", DataType::Int32),
+    ("lts", DataType::Boolean),
+];
+
+/// The rows of a frame that passed validation and those that were quarantined.
+#[derive(Debug, Default)]
+pub struct Validated {
+    pub rows: Vec<ProductCycle>,
+    pub quarantined: Vec<QuarantinedRow>,
+}
+
+/// Validates the frame a collector returned for a product. A frame missing a
+/// required column is rejected as a whole; otherwise each row is checked for
+/// missing values, a version-like name, an EOL date after its release date,
+/// a release date at most `future_release_days` ahead of `today`, and a name
+/// no earlier row has.
+pub fn validate(
+    product: &str,
+    df: &DataFrame,
+    rules: &Validation,
+    today: NaiveDate,
+) -> Result<Validated, Error> {
+    for (name, dtype) in REQUIRED_COLUMNS {
+        let column = df
+            .column(name)
+            .map_err(|_| Error::Schema(format!("{product}: missing column {name}")))?;
+        if column.dtype() != &dtype {
+            return Err(Error::Schema(format!(
+                "{product}: column {name} is {}, expected {dtype}",
+                column.dtype()
+            )));
+        }
+    }
+
+    let missing_name = df.column("name")?.is_null();
+    let missing_lts = df.column("lts")?.is_null();
+    let latest_release = today + chrono::Duration::days(rules.future_release_days);
+    let now = Utc::now();
+
+    let mut validated = Validated::default();
+    let mut seen = HashSet::new();
+    for (i, row) in dataframe_to_product_cycles(df)?.into_iter().enumerate() {
+        let problem = if missing_name.get(i).unwrap_or(false) {
+            Some((QuarantineReason::Schema, "missing name".to_string()))
+        } else if missing_lts.get(i).unwrap_or(false) {
+            Some((QuarantineReason::Schema, "missing lts flag".to_string()))
+        } else {
+            check_row(&row, latest_release, &mut seen)
+        };
+
+        match problem {
+            None => validated.rows.push(row),
+            Some((reason, detail)) => validated.quarantined.push(QuarantinedRow {
+                product: product.to_string(),
+                name: row.name,
+                release_date: row.release_date,
+                eol_date: row.eol_date,
+                reason,
+                detail,
+                quarantined_at: now,
+            }),
+        }
+    }
+    Ok(validated)
+}
+
+fn check_row(
+    row: &ProductCycle,
+    latest_release: NaiveDate,
+    seen: &mut HashSet<String>,
+) -> Option<(QuarantineReason, String)> {
+    let name = row.name.trim();
+    if !name
+        .trim_start_matches('v')
+        .starts_with(|c: char| c.is_ascii_digit())
+        || version_parts(name).is_empty()
+    {
+        return Some((
+            QuarantineReason::UnparseableVersion,
+            format!("{:?} is not a version", row.name),
+        ));
+    }
+    if let (Some(release), Some(eol)) = (row.release_date, row.eol_date)
+        && eol < release
+    {
+        return Some((
+            QuarantineReason::EolBeforeRelease,
+            format!("EOL {eol} precedes release {release}"),
+        ));
+    }
+    if let Some(release) = row.release_date
+        && release > latest_release
+    {
+        return Some((
+            QuarantineReason::FutureRelease,
+            format!("release date {release} is after {latest_release}"),
+        ));
+    }
+    if !seen.insert(name.to_string()) {
+        return Some((
+            QuarantineReason::Duplicate,
+            format!("{name} was already collected"),
+        ));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::product_cycles_to_dataframe;
+    use polars::prelude::Column;
+    use versionwatch_core::domain::product_cycle::EolSource;
+
+    fn today() -> NaiveDate {
+        "2026-10-18".parse().unwrap()
+    }
+
+    fn date(s: &str) -> Option<NaiveDate> {
+        Some(s.parse().unwrap())
+    }
+
+    fn rules() -> Validation {
+        Validation {
+            future_release_days: 30,
+        }
+    }
+
+    fn row(
+        name: &str,
+        release_date: Option<NaiveDate>,
+        eol_date: Option<NaiveDate>,
+    ) -> ProductCycle {
+        ProductCycle {
+            name: name.to_string(),
+            release_date,
+            eol_date,
+            lts: false,
+            eol_source: EolSource::Official,
+            provenance: None,
+            fallback: false,
+        }
+    }
+
+    fn run(rows: Vec<ProductCycle>) -> Validated {
+        let df = product_cycles_to_dataframe(rows).unwrap();
+        validate("node", &df, &rules(), today()).unwrap()
+    }
+
+    fn reasons(validated: &Validated) -> Vec<(&str, QuarantineReason)> {
+        validated
+            .quarantined
+            .iter()
+            .map(|q| (q.name.as_str(), q.reason))
+            .collect()
+    }
+
+    #[test]
+    fn valid_rows_pass() {
+        let validated = run(vec![
+            row("22.11.0", date("2024-10-29"), date("2027-04-30")),
+            row("v20.18.0", date("2024-10-03"), None),
+            row("18", None, None),
+        ]);
+        assert_eq!(validated.rows.len(), 3);
+        assert!(validated.quarantined.is_empty());
+    }
+
+    #[test]
+    fn names_that_are_not_versions_are_quarantined() {
+        let validated = run(vec![
+            row("latest", None, None),
+            row("", None, None),
+            row("v", None, None),
+            row("1.0", None, None),
+        ]);
+        assert_eq!(validated.rows.len(), 1);
+        assert_eq!(
+            reasons(&validated),
+            [
+                ("latest", QuarantineReason::UnparseableVersion),
+                ("", QuarantineReason::UnparseableVersion),
+                ("v", QuarantineReason::UnparseableVersion),
+            ]
+        );
+    }
+
+    #[test]
+    fn eol_before_release_is_quarantined() {
+        let validated = run(vec![
+            row("1.0", date("2021-03-02"), date("2020-01-01")),
+            row("1.1", date("2021-03-02"), date("2021-03-02")),
+        ]);
+        assert_eq!(
+            reasons(&validated),
+            [("1.0", QuarantineReason::EolBeforeRelease)]
+        );
+        assert_eq!(
+            validated.quarantined[0].detail,
+            "EOL 2020-01-01 precedes release 2021-03-02"
+        );
+    }
+
+    #[test]
+    fn releases_beyond_the_allowed_window_are_quarantined() {
+        let validated = run(vec![
+            row("1.0", date("2026-11-17"), None),
+            row("1.1", date("2026-11-18"), None),
+        ]);
+        assert_eq!(
+            reasons(&validated),
+            [("1.1", QuarantineReason::FutureRelease)]
+        );
+    }
+
+    #[test]
+    fn later_duplicates_are_quarantined() {
+        let validated = run(vec![
+            row("1.0", date("2020-01-01"), None),
+            row("1.0", date("2020-02-01"), None),
+            row(" 1.0 ", None, None),
+        ]);
+        assert_eq!(validated.rows.len(), 1);
+        assert_eq!(validated.rows[0].release_date, date("2020-01-01"));
+        assert_eq!(
+            reasons(&validated),
+            [
+                ("1.0", QuarantineReason::Duplicate),
+                (" 1.0 ", QuarantineReason::Duplicate),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_values_are_schema_problems() {
+        let mut df = product_cycles_to_dataframe(vec![
+            row("1.0", None, None),
+            row("1.1", None, None),
+            row("1.2", None, None),
+        ])
+        .unwrap();
+        df.with_column(Column::new("name".into(), [Some("1.0"), None, Some("1.2")]))
+            .unwrap();
+        df.with_column(Column::new("lts".into(), [Some(false), Some(false), None]))
+            .unwrap();
+        let validated = validate("node", &df, &rules(), today()).unwrap();
+        assert_eq!(validated.rows.len(), 1);
+        let details: Vec<(QuarantineReason, &str)> = validated
+            .quarantined
+            .iter()
+            .map(|q| (q.reason, q.detail.as_str()))
+            .collect();
+        assert_eq!(
+            details,
+            [
+                (QuarantineReason::Schema, "missing name"),
+                (QuarantineReason::Schema, "missing lts flag"),
+            ]
+        );
+    }
+
+    #[test]
+    fn frames_missing_a_column_or_with_the_wrong_type_are_rejected() {
+        let df = product_cycles_to_dataframe(vec![row("1.0", None, None)]).unwrap();
+
+        let missing = df.drop("eol_date").unwrap();
+        assert!(matches!(
+            validate("node", &missing, &rules(), today()),
+            Err(Error::Schema(_))
+        ));
+
+        let mut retyped = df.clone();
+        retyped
+            .with_column(Column::new("lts".into(), ["no"]))
+            .unwrap();
+        assert!(matches!(
+            validate("node", &retyped, &rules(), today()),
+            Err(Error::Schema(_))
+        ));
+    }
+}
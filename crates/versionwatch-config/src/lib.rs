@@ -1,7 +1,12 @@
 use std::path::PathBuf;
 
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::Path;
+use versionwatch_core::domain::inventory::InventoryEntry;
+use versionwatch_core::domain::policy::Policy;
+use versionwatch_core::domain::schedule::Schedule;
+use versionwatch_core::domain::support::{LtsRule, SupportPolicy};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -11,6 +16,12 @@ pub enum Error {
     /// The config file is not valid YAML
     #[error("Could not parse config file: {0}")]
     Serde(#[from] serde_yaml::Error),
+    /// The policy file is not a valid lifecycle policy
+    #[error("Could not parse policy file {path}: {source}")]
+    Policy {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
     /// An IO error occurred
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -25,6 +36,132 @@ pub struct Target {
     pub github_source: String,
     #[serde(default)]
     pub cleaning: VersionCleaning,
+    /// Support policy used to infer missing EOL dates, overriding the built-in one
+    #[serde(default)]
+    pub support: Option<SupportPolicy>,
+    /// Which cycles are LTS releases, overriding the built-in rule
+    #[serde(default)]
+    pub lts: Option<LtsRule>,
+    /// Where to read versions from, in priority order, for collectors built
+    /// from sources; empty for the collector's own chain
+    #[serde(default)]
+    pub sources: Vec<SourceConfig>,
+    #[serde(default)]
+    pub source_mode: SourceMode,
+    /// How long a collection may take, overriding `collection.timeout_secs`
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// When the dashboard collects the target, overriding `schedule`
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+}
+
+/// A kind of version source collectors can be built from.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// Tags of a GitHub repository
+    GithubTags,
+    /// Tags of a Docker Hub image
+    Dockerhub,
+    /// A curated fallback dataset of known versions, used when live sources
+    /// are unavailable
+    Static,
+}
+
+impl SourceKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SourceKind::GithubTags => "github_tags",
+            SourceKind::Dockerhub => "dockerhub",
+            SourceKind::Static => "static",
+        }
+    }
+}
+
+/// One source of a target's chain, written as its kind alone or with options:
+///
+/// ```yaml
+/// sources:
+///   - source: github_tags
+///     repository: mysql/mysql-server
+///     tag_regex: '^mysql-(\d+\.\d+\.\d+)$'
+///   - dockerhub
+///   - source: static
+///     file: fallback/mysql.yaml
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(from = "SourceSpec")]
+pub struct SourceConfig {
+    pub kind: SourceKind,
+    /// GitHub repository, e.g. "mysql/mysql-server"
+    pub repository: Option<String>,
+    /// Docker Hub image, e.g. "library/mysql"
+    pub image: Option<String>,
+    /// Only tags matching this regex are kept; its first group, when it has
+    /// one, is taken as the version
+    pub tag_regex: Option<String>,
+    /// Fallback dataset file of a static source, instead of the built-in one
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SourceSpec {
+    Kind(SourceKind),
+    Options {
+        source: SourceKind,
+        repository: Option<String>,
+        image: Option<String>,
+        tag_regex: Option<String>,
+        file: Option<PathBuf>,
+    },
+}
+
+impl From<SourceKind> for SourceConfig {
+    fn from(kind: SourceKind) -> Self {
+        SourceConfig {
+            kind,
+            repository: None,
+            image: None,
+            tag_regex: None,
+            file: None,
+        }
+    }
+}
+
+impl From<SourceSpec> for SourceConfig {
+    fn from(spec: SourceSpec) -> Self {
+        match spec {
+            SourceSpec::Kind(kind) => kind.into(),
+            SourceSpec::Options {
+                source,
+                repository,
+                image,
+                tag_regex,
+                file,
+            } => SourceConfig {
+                kind: source,
+                repository,
+                image,
+                tag_regex,
+                file,
+            },
+        }
+    }
+}
+
+/// How a chain of sources is run.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceMode {
+    /// Query every live source and merge their versions, the first source
+    /// winning on disagreements; static sources only serve when all live
+    /// sources fail
+    #[default]
+    Merge,
+    /// Use the first source, in order, that returns versions
+    Fallback,
 }
 
 fn default_github_source() -> String {
@@ -43,6 +180,133 @@ pub struct Settings {
     pub targets: Vec<Target>,
     #[serde(default)]
     pub github_token: Option<String>,
+    /// Bearer token the dashboard's POST endpoints require; they are refused
+    /// when none is set
+    #[serde(default)]
+    pub api_token: Option<String>,
+    /// Deployed product versions to evaluate against collected data
+    #[serde(default)]
+    pub inventory: Vec<InventoryEntry>,
+    /// Lifecycle policy file evaluated by `policy`, `check` and `/api/policy`
+    #[serde(default)]
+    pub policy: Option<PathBuf>,
+    /// Checks collected rows must pass before they are stored
+    #[serde(default)]
+    pub validation: Validation,
+    /// When a run that returns much less than the last accepted one is held back
+    #[serde(default)]
+    pub regression_guard: RegressionGuard,
+    /// How many collections run at once and how long each may take
+    #[serde(default)]
+    pub collection: Collection,
+    /// When the dashboard collects targets without a schedule of their own
+    #[serde(default)]
+    pub schedule: Schedule,
+}
+
+impl Settings {
+    /// The schedule the dashboard collects a target on.
+    pub fn schedule_for<'a>(&'a self, target: &'a Target) -> &'a Schedule {
+        target.schedule.as_ref().unwrap_or(&self.schedule)
+    }
+}
+
+/// Limits on the collections the dashboard runs concurrently.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Collection {
+    /// Collections running at once across all targets
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
+    /// Collections fetching from the same host at once
+    #[serde(default = "default_per_host")]
+    pub per_host: usize,
+    /// Per-host limits overriding `per_host`, keyed by host name
+    #[serde(default)]
+    pub hosts: HashMap<String, usize>,
+    /// How long a collection may take before it is abandoned
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_max_concurrency() -> usize {
+    8
+}
+
+fn default_per_host() -> usize {
+    2
+}
+
+fn default_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for Collection {
+    fn default() -> Self {
+        Self {
+            max_concurrency: default_max_concurrency(),
+            per_host: default_per_host(),
+            hosts: HashMap::new(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+}
+
+impl Collection {
+    /// The timeout of a target's collections.
+    pub fn timeout(&self, target: &Target) -> std::time::Duration {
+        std::time::Duration::from_secs(target.timeout_secs.unwrap_or(self.timeout_secs))
+    }
+}
+
+/// Thresholds past which a collection run is held back as a degraded source
+/// instead of replacing the last accepted data.
+#[derive(Debug, Deserialize, Clone)]
+pub struct RegressionGuard {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Largest drop in row count, as a percentage of the last accepted run,
+    /// that is accepted
+    #[serde(default = "default_max_row_drop_percent")]
+    pub max_row_drop_percent: f64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_row_drop_percent() -> f64 {
+    50.0
+}
+
+impl Default for RegressionGuard {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_row_drop_percent: default_max_row_drop_percent(),
+        }
+    }
+}
+
+/// Thresholds of the validation collected rows go through; rows that fail are
+/// quarantined instead of stored.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Validation {
+    /// How many days ahead of today a release date may be, for releases
+    /// announced with a date
+    #[serde(default = "default_future_release_days")]
+    pub future_release_days: i64,
+}
+
+fn default_future_release_days() -> i64 {
+    30
+}
+
+impl Default for Validation {
+    fn default() -> Self {
+        Self {
+            future_release_days: default_future_release_days(),
+        }
+    }
 }
 
 /// Loads the configuration from the given path.
@@ -59,6 +323,18 @@ pub fn load(config_path: &Path) -> Result<Settings, Error> {
             settings.github_token = Some(token);
         }
     }
+    if settings.api_token.is_none() {
+        settings.api_token = std::env::var("VERSIONWATCH_API_TOKEN").ok();
+    }
 
     Ok(settings)
 }
+
+/// Loads a lifecycle policy from a YAML file.
+pub fn load_policy(path: &Path) -> Result<Policy, Error> {
+    let contents = std::fs::read_to_string(path)?;
+    serde_yaml::from_str(&contents).map_err(|source| Error::Policy {
+        path: path.to_path_buf(),
+        source,
+    })
+}
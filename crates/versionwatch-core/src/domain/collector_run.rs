@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How a collection run of a target ended.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// Collected from live sources and accepted
+    Active,
+    /// Every live source failed; served from a fallback dataset
+    Fallback,
+    /// Held back by the regression guard until acknowledged
+    Degraded,
+    /// Abandoned after running longer than the target's timeout
+    TimedOut,
+    Failed,
+}
+
+impl RunStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RunStatus::Active => "active",
+            RunStatus::Fallback => "fallback",
+            RunStatus::Degraded => "degraded",
+            RunStatus::TimedOut => "timeout",
+            RunStatus::Failed => "failed",
+        }
+    }
+
+    /// Parses a label written by `label`, defaulting to failed.
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "active" => RunStatus::Active,
+            "fallback" => RunStatus::Fallback,
+            "degraded" => RunStatus::Degraded,
+            "timeout" => RunStatus::TimedOut,
+            _ => RunStatus::Failed,
+        }
+    }
+
+    /// Status shown on the dashboard.
+    pub fn display(&self) -> &'static str {
+        match self {
+            RunStatus::Active => "Active",
+            RunStatus::Fallback => "Degraded (fallback)",
+            RunStatus::Degraded => "Source Degraded",
+            RunStatus::TimedOut => "Timed Out",
+            RunStatus::Failed => "Failed",
+        }
+    }
+
+    /// Whether the run's data was collected and accepted.
+    pub fn succeeded(&self) -> bool {
+        matches!(self, RunStatus::Active | RunStatus::Fallback)
+    }
+}
+
+/// One collection run of a target.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CollectorRun {
+    pub target: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub status: RunStatus,
+    /// Rows that passed validation
+    pub rows: usize,
+    /// Rows held back by validation
+    pub quarantined: usize,
+    /// Category of the error of a failed run, e.g. "rate_limited"
+    pub error_kind: Option<String>,
+    pub error_message: Option<String>,
+    /// Sources the rows came from, e.g. "github_tags+dockerhub"
+    pub source: Option<String>,
+}
@@ -0,0 +1,129 @@
+use super::lifecycle::{compare_versions, is_prerelease, version_parts};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Package ecosystem a library dependency is published to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    PyPi,
+    RubyGems,
+    Packagist,
+    Go,
+}
+
+impl Ecosystem {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::PyPi => "pypi",
+            Ecosystem::RubyGems => "rubygems",
+            Ecosystem::Packagist => "packagist",
+            Ecosystem::Go => "go",
+        }
+    }
+}
+
+/// A library dependency pinned by a lockfile.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Dependency {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub version: String,
+}
+
+/// A published version of a package, as listed by its registry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PackageRelease {
+    pub version: String,
+    pub published: Option<NaiveDate>,
+}
+
+/// How stale a dependency is compared to what its registry offers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DependencyStatus {
+    #[serde(flatten)]
+    pub dependency: Dependency,
+    pub latest: Option<String>,
+    pub latest_published: Option<NaiveDate>,
+    pub published: Option<NaiveDate>,
+    /// Stable releases newer than the locked version
+    pub versions_behind: Option<usize>,
+    pub majors_behind: Option<u64>,
+    /// Days since the locked version was published
+    pub age_days: Option<i64>,
+}
+
+/// Evaluates a dependency against the releases of its package.
+///
+/// `latest` is the registry's notion of the latest release when it has one
+/// (npm dist-tags, Go proxy); otherwise the newest stable release is used.
+/// Pre-releases are ignored unless the dependency itself is one.
+pub fn evaluate(
+    dependency: &Dependency,
+    releases: &[PackageRelease],
+    latest: Option<&str>,
+    today: NaiveDate,
+) -> DependencyStatus {
+    let include_prereleases = is_prerelease(&dependency.version);
+    let stable: Vec<&PackageRelease> = releases
+        .iter()
+        .filter(|r| include_prereleases || !is_prerelease(&r.version))
+        .collect();
+
+    let latest = latest.map(str::to_string).or_else(|| {
+        stable
+            .iter()
+            .max_by(|a, b| compare_versions(&a.version, &b.version))
+            .map(|r| r.version.clone())
+    });
+    let published_on = |version: &str| {
+        releases
+            .iter()
+            .find(|r| same_version(&r.version, version))
+            .and_then(|r| r.published)
+    };
+
+    let versions_behind = latest.as_ref().map(|latest| {
+        stable
+            .iter()
+            .filter(|r| {
+                compare_versions(&r.version, &dependency.version) == Ordering::Greater
+                    && compare_versions(&r.version, latest) != Ordering::Greater
+            })
+            .count()
+    });
+
+    DependencyStatus {
+        dependency: dependency.clone(),
+        latest_published: latest.as_deref().and_then(published_on),
+        published: published_on(&dependency.version),
+        majors_behind: latest
+            .as_deref()
+            .map(|latest| majors_between(&dependency.version, latest)),
+        age_days: published_on(&dependency.version).map(|date| (today - date).num_days()),
+        versions_behind,
+        latest,
+    }
+}
+
+/// Breaking releases between two versions. Following semver, minor releases
+/// of 0.x versions count as breaking.
+fn majors_between(current: &str, latest: &str) -> u64 {
+    let current = version_parts(current);
+    let latest = version_parts(latest);
+    let part = |parts: &[u64], i: usize| parts.get(i).copied().unwrap_or(0);
+
+    match (part(&current, 0), part(&latest, 0)) {
+        (0, 0) => part(&latest, 1).saturating_sub(part(&current, 1)),
+        (current, latest) => latest.saturating_sub(current),
+    }
+}
+
+fn same_version(a: &str, b: &str) -> bool {
+    a.trim_start_matches('v') == b.trim_start_matches('v')
+}
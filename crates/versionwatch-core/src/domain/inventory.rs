@@ -0,0 +1,150 @@
+use super::lifecycle::{Lifecycle, SupportStatus, compare_versions, is_behind, version_parts};
+use super::software_version::SoftwareVersion;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::Ordering;
+
+/// A product version actually deployed somewhere.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InventoryEntry {
+    pub service: String,
+    pub environment: String,
+    pub product: String,
+    #[serde(alias = "version")]
+    pub current_version: String,
+    /// Free-form labels policies can be scoped by, e.g. "pci" or "customer-facing"
+    #[serde(default, deserialize_with = "deserialize_tags")]
+    pub tags: Vec<String>,
+}
+
+/// Tags are a list in YAML and a comma-separated cell in CSV.
+fn deserialize_tags<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tags {
+        List(Vec<String>),
+        Joined(String),
+    }
+
+    Ok(match Tags::deserialize(deserializer)? {
+        Tags::List(tags) => tags,
+        Tags::Joined(tags) => tags
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+/// How far a deployed version drifted from what upstream currently ships.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DriftStatus {
+    UpToDate,
+    /// A newer patch release exists in the same cycle.
+    PatchBehind,
+    /// A newer cycle exists within the same major line.
+    MinorBehind,
+    Eol,
+    /// The product or version is not part of the collected data, or its cycle
+    /// was superseded and has no EOL date.
+    Unknown,
+}
+
+impl DriftStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DriftStatus::UpToDate => "up to date",
+            DriftStatus::PatchBehind => "patch behind",
+            DriftStatus::MinorBehind => "minor behind",
+            DriftStatus::Eol => "EOL",
+            DriftStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// Result of evaluating an inventory entry against collected data.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InventoryStatus {
+    #[serde(flatten)]
+    pub entry: InventoryEntry,
+    pub status: DriftStatus,
+    pub cycle: Option<String>,
+    pub version: SoftwareVersion,
+}
+
+/// Evaluates a deployed version against the lifecycle of its product.
+pub fn evaluate(
+    entry: &InventoryEntry,
+    lifecycle: Option<&Lifecycle>,
+    today: NaiveDate,
+) -> InventoryStatus {
+    let mut version = SoftwareVersion {
+        name: entry.product.clone(),
+        current_version: entry.current_version.clone(),
+        latest_version: String::new(),
+        latest_lts_version: None,
+        is_lts: false,
+        eol_date: None,
+        release_notes_url: None,
+        cve_count: 0,
+    };
+
+    let Some(lifecycle) = lifecycle else {
+        return InventoryStatus {
+            entry: entry.clone(),
+            status: DriftStatus::Unknown,
+            cycle: None,
+            version,
+        };
+    };
+
+    version.latest_version = lifecycle
+        .latest()
+        .map(|c| c.latest.clone())
+        .unwrap_or_default();
+    version.latest_lts_version = lifecycle
+        .cycles()
+        .iter()
+        .find(|c| c.lts)
+        .map(|c| c.latest.clone());
+
+    let Some(cycle) = lifecycle.cycle_for_version(&entry.current_version) else {
+        return InventoryStatus {
+            entry: entry.clone(),
+            status: DriftStatus::Unknown,
+            cycle: None,
+            version,
+        };
+    };
+
+    version.is_lts = cycle.lts;
+    version.eol_date = cycle.eol_date.and_then(to_time_date);
+
+    let major = version_parts(&cycle.name).first().copied();
+    let newer_minor = lifecycle.cycles().iter().any(|c| {
+        version_parts(&c.name).first().copied() == major
+            && compare_versions(&c.name, &cycle.name) == Ordering::Greater
+    });
+
+    let status = match lifecycle.status(cycle, today) {
+        SupportStatus::Eol => DriftStatus::Eol,
+        SupportStatus::Unknown => DriftStatus::Unknown,
+        _ if is_behind(&entry.current_version, &cycle.latest) => DriftStatus::PatchBehind,
+        _ if newer_minor => DriftStatus::MinorBehind,
+        _ => DriftStatus::UpToDate,
+    };
+
+    InventoryStatus {
+        entry: entry.clone(),
+        status,
+        cycle: Some(cycle.name.clone()),
+        version,
+    }
+}
+
+fn to_time_date(date: NaiveDate) -> Option<time::Date> {
+    let month = time::Month::try_from(date.month() as u8).ok()?;
+    time::Date::from_calendar_date(date.year(), month, date.day() as u8).ok()
+}
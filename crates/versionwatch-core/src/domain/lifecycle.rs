@@ -0,0 +1,212 @@
+use super::product_cycle::{EolSource, ProductCycle};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Support status of a release cycle on a given day.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SupportStatus {
+    /// The cycle is the newest one and still maintained.
+    Supported,
+    /// A newer cycle exists but this one has not reached its EOL date yet.
+    SecurityOnly,
+    /// The cycle reached its end-of-life date.
+    Eol,
+    /// There is not enough data to tell.
+    Unknown,
+}
+
+impl SupportStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SupportStatus::Supported => "supported",
+            SupportStatus::SecurityOnly => "security-only",
+            SupportStatus::Eol => "EOL",
+            SupportStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// A release cycle aggregated from all the rows a collector returned for it.
+///
+/// Collectors either return one row per cycle (PostgreSQL, MongoDB) or one row
+/// per release (Node.js, PHP, Go). Both shapes are folded into the same view.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Cycle {
+    /// Cycle name, e.g. "18" for Node.js or "3.12" for Python
+    pub name: String,
+    pub release_date: Option<NaiveDate>,
+    pub eol_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub eol_source: EolSource,
+    pub lts: bool,
+    /// Latest release known for this cycle, e.g. "18.20.4"
+    pub latest: String,
+    pub latest_release_date: Option<NaiveDate>,
+}
+
+/// Lifecycle of a product, with its cycles sorted from newest to oldest.
+#[derive(Debug, Clone)]
+pub struct Lifecycle {
+    product: String,
+    cycles: Vec<Cycle>,
+}
+
+impl Lifecycle {
+    pub fn new(product: &str, rows: &[ProductCycle]) -> Self {
+        let mut groups: BTreeMap<String, Vec<&ProductCycle>> = BTreeMap::new();
+        for row in rows.iter().filter(|r| !is_prerelease(&r.name)) {
+            if let Some(key) = cycle_key(product, &row.name) {
+                groups.entry(key).or_default().push(row);
+            }
+        }
+
+        let mut cycles: Vec<Cycle> = groups
+            .into_iter()
+            .map(|(name, rows)| {
+                let latest = rows
+                    .iter()
+                    .max_by(|a, b| compare_versions(&a.name, &b.name))
+                    .expect("cycle groups are never empty");
+                let eol = rows
+                    .iter()
+                    .filter(|r| r.eol_date.is_some())
+                    .max_by_key(|r| r.eol_date);
+                Cycle {
+                    release_date: rows.iter().filter_map(|r| r.release_date).min(),
+                    eol_date: eol.and_then(|r| r.eol_date),
+                    eol_source: eol.map(|r| r.eol_source).unwrap_or_default(),
+                    lts: rows.iter().any(|r| r.lts),
+                    latest: latest.name.clone(),
+                    latest_release_date: latest.release_date,
+                    name,
+                }
+            })
+            .collect();
+        cycles.sort_by(|a, b| compare_versions(&b.name, &a.name));
+
+        Self {
+            product: product.to_string(),
+            cycles,
+        }
+    }
+
+    pub fn product(&self) -> &str {
+        &self.product
+    }
+
+    /// Cycles sorted from newest to oldest.
+    pub fn cycles(&self) -> &[Cycle] {
+        &self.cycles
+    }
+
+    /// The newest cycle of the product.
+    pub fn latest(&self) -> Option<&Cycle> {
+        self.cycles.first()
+    }
+
+    /// Finds the cycle a version (or a partial version such as "3" or "18") belongs to.
+    ///
+    /// Partial versions resolve to the newest cycle they are a prefix of, the same way
+    /// `python:3` resolves to the newest 3.x image.
+    pub fn cycle_for_version(&self, version: &str) -> Option<&Cycle> {
+        let parts = version_parts(version);
+        if parts.is_empty() {
+            return None;
+        }
+        let exact = cycle_key(&self.product, version)
+            .and_then(|key| self.cycles.iter().find(|c| c.name == key));
+        if exact.is_some() {
+            return exact;
+        }
+        self.cycles
+            .iter()
+            .find(|c| version_parts(&c.name).starts_with(&parts))
+    }
+
+    /// Status of a cycle on the given day.
+    pub fn status(&self, cycle: &Cycle, today: NaiveDate) -> SupportStatus {
+        let superseded = self
+            .latest()
+            .is_some_and(|latest| compare_versions(&latest.name, &cycle.name) == Ordering::Greater);
+
+        match cycle.eol_date {
+            Some(eol) if eol <= today => SupportStatus::Eol,
+            Some(_) if superseded => SupportStatus::SecurityOnly,
+            _ if !superseded => SupportStatus::Supported,
+            _ => SupportStatus::Unknown,
+        }
+    }
+}
+
+/// Returns the cycle a version belongs to for the given product.
+///
+/// Most products cut cycles on `major.minor`; a few only on `major`.
+pub fn cycle_key(product: &str, version: &str) -> Option<String> {
+    let parts = version_parts(version);
+    let major = *parts.first()?;
+    let depth = match product {
+        "node" | "eclipse-temurin" | "docker" => 1,
+        "postgresql" if major >= 10 => 1,
+        _ => 2,
+    };
+
+    Some(
+        parts
+            .iter()
+            .take(depth)
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+/// Compares two version strings component by component.
+///
+/// Only the leading numeric components are considered, so "17.0.2+8" and
+/// "3.8-slim" compare as 17.0.2 and 3.8.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    version_parts(a).cmp(&version_parts(b))
+}
+
+/// Tells whether `current` is older than `latest`.
+///
+/// Partial versions such as "3.11" float to the newest matching release, so they
+/// are never behind the latest release they are a prefix of.
+pub fn is_behind(current: &str, latest: &str) -> bool {
+    let current = version_parts(current);
+    let latest = version_parts(latest);
+    if current.len() < latest.len() && latest.starts_with(&current) {
+        return false;
+    }
+    current < latest
+}
+
+/// Extracts the leading numeric components of a version string.
+pub fn version_parts(version: &str) -> Vec<u64> {
+    let version = version.trim().trim_start_matches('v');
+    let end = version
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(version.len());
+
+    version[..end]
+        .split('.')
+        .map_while(|p| p.parse().ok())
+        .collect()
+}
+
+/// Tells whether a version string denotes a pre-release (alpha, beta, rc, ...).
+pub fn is_prerelease(version: &str) -> bool {
+    let version = version.trim().trim_start_matches('v');
+    let rest = version
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.')
+        .to_ascii_lowercase();
+    let rest = rest.trim_start_matches(['-', '_']);
+
+    ["alpha", "beta", "rc", "dev", "pre", "preview", "snapshot"]
+        .iter()
+        .any(|marker| rest.starts_with(marker))
+        || (rest.starts_with(['a', 'b']) && rest[1..].starts_with(|c: char| c.is_ascii_digit()))
+}
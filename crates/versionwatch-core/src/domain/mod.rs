@@ -1,2 +1,12 @@
+pub mod collector_run;
+pub mod dependency;
+pub mod inventory;
+pub mod lifecycle;
+pub mod policy;
 pub mod product_cycle;
+pub mod provenance;
+pub mod quarantine;
+pub mod regression;
+pub mod schedule;
 pub mod software_version;
+pub mod support;
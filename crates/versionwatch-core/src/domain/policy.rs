@@ -0,0 +1,350 @@
+use super::lifecycle::{Cycle, Lifecycle, SupportStatus, compare_versions, is_behind};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// How serious a policy violation is, ordered from best to worst.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Pass,
+    #[default]
+    Warn,
+    Fail,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Pass => "pass",
+            Severity::Warn => "warn",
+            Severity::Fail => "fail",
+        }
+    }
+
+    /// Process exit code: 0 to pass, 1 to warn, 2 to fail.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Severity::Pass => 0,
+            Severity::Warn => 1,
+            Severity::Fail => 2,
+        }
+    }
+}
+
+/// A lifecycle policy: rules deployed versions must follow, and time-boxed
+/// exceptions to them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Policy {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    #[serde(default)]
+    pub exceptions: Vec<Exception>,
+}
+
+/// What a rule applies to. Empty lists match everything.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Scope {
+    #[serde(default)]
+    pub products: Vec<String>,
+    /// Matches subjects carrying any of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub environments: Vec<String>,
+}
+
+impl Scope {
+    fn matches(&self, subject: &Subject) -> bool {
+        let listed =
+            |list: &[String], value: &str| list.is_empty() || list.iter().any(|v| v == value);
+        listed(&self.products, subject.product)
+            && (self.tags.is_empty() || subject.tags.iter().any(|t| self.tags.contains(t)))
+            && (self.environments.is_empty()
+                || subject
+                    .environment
+                    .is_some_and(|e| listed(&self.environments, e)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Rule {
+    /// Identifier reported with violations and referenced by exceptions
+    pub id: String,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(flatten)]
+    pub scope: Scope,
+    pub condition: Condition,
+    /// How to fix a violation. `{product}`, `{version}`, `{cycle}`,
+    /// `{cycle_latest}` (latest release of the cycle) and `{latest}` (latest
+    /// release of the product) are replaced with the subject's values.
+    pub remediation: Option<String>,
+}
+
+/// What a rule requires of the cycle a version belongs to.
+///
+/// Written as a name (`eol`, `lts_only`) or a single-key map carrying the
+/// threshold (`eol_within_days: 90`).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "ConditionSpec", into = "ConditionSpec")]
+pub enum Condition {
+    /// The cycle has not reached its EOL date.
+    Eol,
+    /// The cycle does not reach its EOL date within this many days.
+    EolWithinDays(i64),
+    /// At most this many newer cycles exist, i.e. minor versions for products
+    /// cut on `major.minor` and major versions for Node.js or Java.
+    MaxCyclesBehind(usize),
+    /// The cycle is a long-term support one.
+    LtsOnly,
+    /// The cycle's latest release is installed within this many days.
+    PatchWithinDays(i64),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ConditionSpec {
+    Name(String),
+    Threshold(BTreeMap<String, i64>),
+}
+
+impl TryFrom<ConditionSpec> for Condition {
+    type Error = String;
+
+    fn try_from(spec: ConditionSpec) -> Result<Self, Self::Error> {
+        let (name, value) = match spec {
+            ConditionSpec::Name(name) => (name, None),
+            ConditionSpec::Threshold(map) if map.len() == 1 => {
+                let (name, value) = map.into_iter().next().expect("map has one entry");
+                (name, Some(value))
+            }
+            ConditionSpec::Threshold(_) => {
+                return Err("a condition map must have exactly one key".to_string());
+            }
+        };
+        match (name.as_str(), value) {
+            ("eol", None) => Ok(Condition::Eol),
+            ("lts_only", None) => Ok(Condition::LtsOnly),
+            ("eol_within_days", Some(days)) => Ok(Condition::EolWithinDays(days)),
+            ("max_cycles_behind", Some(max)) => usize::try_from(max)
+                .map(Condition::MaxCyclesBehind)
+                .map_err(|_| "max_cycles_behind must not be negative".to_string()),
+            ("patch_within_days", Some(days)) => Ok(Condition::PatchWithinDays(days)),
+            _ => Err(format!(
+                "unknown condition `{name}`, expected eol, lts_only, eol_within_days, \
+                 max_cycles_behind or patch_within_days"
+            )),
+        }
+    }
+}
+
+impl From<Condition> for ConditionSpec {
+    fn from(condition: Condition) -> Self {
+        let threshold = |name: &str, value: i64| {
+            ConditionSpec::Threshold(BTreeMap::from([(name.to_string(), value)]))
+        };
+        match condition {
+            Condition::Eol => ConditionSpec::Name("eol".to_string()),
+            Condition::LtsOnly => ConditionSpec::Name("lts_only".to_string()),
+            Condition::EolWithinDays(days) => threshold("eol_within_days", days),
+            Condition::MaxCyclesBehind(max) => threshold("max_cycles_behind", max as i64),
+            Condition::PatchWithinDays(days) => threshold("patch_within_days", days),
+        }
+    }
+}
+
+/// A rule waived for some subjects until a given date.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Exception {
+    /// Identifier of the waived rule
+    pub rule: String,
+    #[serde(flatten)]
+    pub scope: Scope,
+    /// Service names, or scanned file paths, the exception is limited to
+    #[serde(default)]
+    pub subjects: Vec<String>,
+    /// Last day the exception applies
+    pub expires: NaiveDate,
+    pub justification: String,
+}
+
+impl Exception {
+    fn applies(&self, rule: &str, subject: &Subject, today: NaiveDate) -> bool {
+        self.rule == rule
+            && today <= self.expires
+            && self.scope.matches(subject)
+            && (self.subjects.is_empty() || self.subjects.iter().any(|s| s == subject.name))
+    }
+}
+
+/// A deployed or referenced version to evaluate, such as an inventory entry or
+/// a scan finding.
+#[derive(Debug, Clone, Copy)]
+pub struct Subject<'a> {
+    /// Service name or file location
+    pub name: &'a str,
+    pub product: &'a str,
+    pub version: &'a str,
+    pub environment: Option<&'a str>,
+    pub tags: &'a [String],
+}
+
+/// A rule broken by a subject.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Violation {
+    pub rule: String,
+    pub severity: Severity,
+    pub subject: String,
+    pub product: String,
+    pub version: String,
+    pub environment: Option<String>,
+    pub cycle: String,
+    pub message: String,
+    pub remediation: String,
+    /// The exception waiving this violation, if any
+    pub waiver: Option<Waiver>,
+}
+
+impl Violation {
+    /// Severity once exceptions are taken into account.
+    pub fn effective_severity(&self) -> Severity {
+        match self.waiver {
+            Some(_) => Severity::Pass,
+            None => self.severity,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Waiver {
+    pub expires: NaiveDate,
+    pub justification: String,
+}
+
+impl Policy {
+    /// Evaluates a subject against every rule in scope.
+    ///
+    /// Subjects whose version matches no collected cycle cannot be judged and
+    /// produce no violations.
+    pub fn evaluate(
+        &self,
+        subject: &Subject,
+        lifecycle: &Lifecycle,
+        today: NaiveDate,
+    ) -> Vec<Violation> {
+        let Some(cycle) = lifecycle.cycle_for_version(subject.version) else {
+            return Vec::new();
+        };
+
+        self.rules
+            .iter()
+            .filter(|rule| rule.scope.matches(subject))
+            .filter_map(|rule| {
+                let message = rule.condition.check(subject, lifecycle, cycle, today)?;
+                let waiver = self
+                    .exceptions
+                    .iter()
+                    .find(|e| e.applies(&rule.id, subject, today))
+                    .map(|e| Waiver {
+                        expires: e.expires,
+                        justification: e.justification.clone(),
+                    });
+                Some(Violation {
+                    rule: rule.id.clone(),
+                    severity: rule.severity,
+                    subject: subject.name.to_string(),
+                    product: subject.product.to_string(),
+                    version: subject.version.to_string(),
+                    environment: subject.environment.map(str::to_string),
+                    cycle: cycle.name.clone(),
+                    message,
+                    remediation: remediation(rule, subject, lifecycle, cycle),
+                    waiver,
+                })
+            })
+            .collect()
+    }
+
+    /// Exceptions past their expiry date, which no longer waive anything.
+    pub fn expired_exceptions(&self, today: NaiveDate) -> impl Iterator<Item = &Exception> {
+        self.exceptions.iter().filter(move |e| e.expires < today)
+    }
+}
+
+impl Condition {
+    /// Describes how the subject breaks the condition, or returns `None` if it holds.
+    fn check(
+        &self,
+        subject: &Subject,
+        lifecycle: &Lifecycle,
+        cycle: &Cycle,
+        today: NaiveDate,
+    ) -> Option<String> {
+        let name = format!("{} {}", subject.product, cycle.name);
+        match *self {
+            Condition::Eol => (lifecycle.status(cycle, today) == SupportStatus::Eol).then(|| {
+                let eol = cycle
+                    .eol_date
+                    .map(|d| format!(" on {d}"))
+                    .unwrap_or_default();
+                format!("{name} reached end of life{eol}")
+            }),
+            Condition::EolWithinDays(days) => {
+                let eol = cycle.eol_date?;
+                let remaining = (eol - today).num_days();
+                (remaining > 0 && remaining <= days)
+                    .then(|| format!("{name} reaches end of life on {eol}, in {remaining} days"))
+            }
+            Condition::MaxCyclesBehind(max) => {
+                let behind = lifecycle
+                    .cycles()
+                    .iter()
+                    .filter(|c| compare_versions(&c.name, &cycle.name) == Ordering::Greater)
+                    .count();
+                (behind > max).then(|| {
+                    let cycles = if behind == 1 { "cycle" } else { "cycles" };
+                    format!("{name} is {behind} {cycles} behind the latest, at most {max} allowed")
+                })
+            }
+            Condition::LtsOnly => {
+                (!cycle.lts).then(|| format!("{name} is not a long-term support release"))
+            }
+            Condition::PatchWithinDays(days) => {
+                if !is_behind(subject.version, &cycle.latest) {
+                    return None;
+                }
+                let released = cycle.latest_release_date?;
+                let age = (today - released).num_days();
+                (age > days).then(|| {
+                    format!(
+                        "{} was released {age} days ago on {released}, {} is still on {}",
+                        cycle.latest, subject.product, subject.version
+                    )
+                })
+            }
+        }
+    }
+}
+
+fn remediation(rule: &Rule, subject: &Subject, lifecycle: &Lifecycle, cycle: &Cycle) -> String {
+    let latest = lifecycle.latest().map(|c| c.latest.as_str()).unwrap_or("-");
+    let template = match (&rule.remediation, rule.condition) {
+        (Some(text), _) => text.as_str(),
+        (None, Condition::Eol | Condition::EolWithinDays(_)) => {
+            "Upgrade {product} to a supported cycle, such as {latest}"
+        }
+        (None, Condition::MaxCyclesBehind(_)) => {
+            "Upgrade {product} to a more recent cycle, such as {latest}"
+        }
+        (None, Condition::LtsOnly) => "Move {product} to a long-term support cycle",
+        (None, Condition::PatchWithinDays(_)) => "Update {product} {cycle} to {cycle_latest}",
+    };
+    template
+        .replace("{product}", subject.product)
+        .replace("{version}", subject.version)
+        .replace("{cycle_latest}", &cycle.latest)
+        .replace("{cycle}", &cycle.name)
+        .replace("{latest}", latest)
+}
@@ -1,7 +1,46 @@
+use super::provenance::Provenance;
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct ProductCycle {
     pub name: String,
     pub release_date: Option<chrono::NaiveDate>,
     pub eol_date: Option<chrono::NaiveDate>,
     pub lts: bool,
+    #[serde(default)]
+    pub eol_source: EolSource,
+    /// Where the row comes from, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    /// Whether the row was served from a curated fallback dataset rather than
+    /// a live source
+    #[serde(default)]
+    pub fallback: bool,
+}
+
+/// Where the end-of-life date of a row comes from.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EolSource {
+    /// Published by the vendor or its release data
+    #[default]
+    Official,
+    /// Derived from the product's support policy
+    Inferred,
+}
+
+impl EolSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EolSource::Official => "official",
+            EolSource::Inferred => "inferred",
+        }
+    }
+
+    /// Parses a label written by `label`, defaulting to official.
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "inferred" => EolSource::Inferred,
+            _ => EolSource::Official,
+        }
+    }
 }
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A source and when it was fetched.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Sourced {
+    /// Source name, e.g. "github_tags" or "dockerhub"
+    pub source: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Where a collected row and each of its fields come from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// Sources that listed the version, in priority order
+    pub sources: Vec<Sourced>,
+    /// Source of each field, keyed by field name; fields left out come from
+    /// the first source
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, Sourced>,
+    /// Fields the sources disagreed on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Provenance {
+    /// Provenance of a row that came from a single source.
+    pub fn single(source: &str, fetched_at: DateTime<Utc>) -> Self {
+        Self {
+            sources: vec![Sourced {
+                source: source.to_string(),
+                fetched_at,
+            }],
+            fields: BTreeMap::new(),
+            conflicts: Vec::new(),
+        }
+    }
+}
+
+/// Values sources gave for the same field of a version. The first one is kept.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub field: String,
+    /// Values in source priority order
+    pub values: Vec<SourcedValue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SourcedValue {
+    pub source: String,
+    pub value: String,
+}
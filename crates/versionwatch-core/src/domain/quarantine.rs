@@ -0,0 +1,56 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Why a collected row was rejected by validation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuarantineReason {
+    /// A required value is missing
+    Schema,
+    /// The name is not a version number
+    UnparseableVersion,
+    /// The EOL date precedes the release date
+    EolBeforeRelease,
+    /// An earlier row already has the same name
+    Duplicate,
+    /// The release date is further in the future than allowed
+    FutureRelease,
+}
+
+impl QuarantineReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            QuarantineReason::Schema => "schema",
+            QuarantineReason::UnparseableVersion => "unparseable_version",
+            QuarantineReason::EolBeforeRelease => "eol_before_release",
+            QuarantineReason::Duplicate => "duplicate",
+            QuarantineReason::FutureRelease => "future_release",
+        }
+    }
+
+    /// Parses a label written by `label`.
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "schema" => Some(QuarantineReason::Schema),
+            "unparseable_version" => Some(QuarantineReason::UnparseableVersion),
+            "eol_before_release" => Some(QuarantineReason::EolBeforeRelease),
+            "duplicate" => Some(QuarantineReason::Duplicate),
+            "future_release" => Some(QuarantineReason::FutureRelease),
+            _ => None,
+        }
+    }
+}
+
+/// A collected row held back from persistence, with why.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuarantinedRow {
+    pub product: String,
+    /// The row's name as collected, empty when it had none
+    pub name: String,
+    pub release_date: Option<NaiveDate>,
+    pub eol_date: Option<NaiveDate>,
+    pub reason: QuarantineReason,
+    /// What exactly was wrong, e.g. "EOL 2020-01-01 precedes release 2021-03-02"
+    pub detail: String,
+    pub quarantined_at: DateTime<Utc>,
+}
@@ -0,0 +1,62 @@
+use super::product_cycle::ProductCycle;
+use serde::{Deserialize, Serialize};
+
+/// How much a collection run returned, compared between runs to catch sources
+/// that suddenly return much less.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RunSummary {
+    pub rows: usize,
+    /// Rows with a release date
+    pub release_dates: usize,
+    /// Rows with an EOL date
+    pub eol_dates: usize,
+}
+
+impl RunSummary {
+    pub fn of(rows: &[ProductCycle]) -> Self {
+        Self {
+            rows: rows.len(),
+            release_dates: rows.iter().filter(|r| r.release_date.is_some()).count(),
+            eol_dates: rows.iter().filter(|r| r.eol_date.is_some()).count(),
+        }
+    }
+
+    /// How the run degrades from the last accepted one: a row count that
+    /// dropped by more than `max_row_drop_percent`, or date columns that had
+    /// values and are now empty. Empty when the run can be accepted.
+    pub fn degradations(&self, accepted: &RunSummary, max_row_drop_percent: f64) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if accepted.rows > 0 {
+            let drop =
+                100.0 * accepted.rows.saturating_sub(self.rows) as f64 / accepted.rows as f64;
+            if drop > max_row_drop_percent {
+                reasons.push(format!(
+                    "row count dropped {drop:.0}% ({} → {})",
+                    accepted.rows, self.rows
+                ));
+            }
+        }
+        if accepted.release_dates > 0 && self.release_dates == 0 {
+            reasons.push("release dates are empty".to_string());
+        }
+        if accepted.eol_dates > 0 && self.eol_dates == 0 {
+            reasons.push("EOL dates are empty".to_string());
+        }
+        reasons
+    }
+}
+
+/// Versions whose EOL date the last accepted run had and `rows` leave out.
+/// Versions `rows` do not list at all are not counted; a shrinking row count
+/// is caught by [`RunSummary::degradations`].
+pub fn lost_eol_dates(rows: &[ProductCycle], accepted: &[ProductCycle]) -> Vec<String> {
+    rows.iter()
+        .filter(|row| row.eol_date.is_none())
+        .filter(|row| {
+            accepted
+                .iter()
+                .any(|a| a.name == row.name && a.eol_date.is_some())
+        })
+        .map(|row| row.name.clone())
+        .collect()
+}
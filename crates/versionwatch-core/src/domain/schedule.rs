@@ -0,0 +1,325 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+/// When a target is collected: every `interval_secs` seconds or at the times
+/// a cron expression matches, delayed by up to `jitter_secs` at random so
+/// targets sharing a schedule do not all run at once.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "ScheduleSpec", into = "ScheduleSpec")]
+pub struct Schedule {
+    pub every: Every,
+    pub jitter_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Every {
+    Interval(u64),
+    Cron(Cron),
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScheduleSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    interval_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cron: Option<String>,
+    #[serde(default)]
+    jitter_secs: u64,
+}
+
+impl TryFrom<ScheduleSpec> for Schedule {
+    type Error = String;
+
+    fn try_from(spec: ScheduleSpec) -> Result<Self, Self::Error> {
+        let every = match (spec.interval_secs, spec.cron) {
+            (Some(0), None) => return Err("interval_secs must be positive".to_string()),
+            (Some(secs), None) => Every::Interval(secs),
+            (None, Some(expr)) => Every::Cron(expr.parse()?),
+            _ => return Err("a schedule needs exactly one of interval_secs and cron".to_string()),
+        };
+        Ok(Schedule {
+            every,
+            jitter_secs: spec.jitter_secs,
+        })
+    }
+}
+
+impl From<Schedule> for ScheduleSpec {
+    fn from(schedule: Schedule) -> Self {
+        let (interval_secs, cron) = match schedule.every {
+            Every::Interval(secs) => (Some(secs), None),
+            Every::Cron(cron) => (None, Some(cron.expr)),
+        };
+        ScheduleSpec {
+            interval_secs,
+            cron,
+            jitter_secs: schedule.jitter_secs,
+        }
+    }
+}
+
+impl Default for Schedule {
+    /// Every five minutes.
+    fn default() -> Self {
+        Schedule {
+            every: Every::Interval(300),
+            jitter_secs: 0,
+        }
+    }
+}
+
+impl Schedule {
+    /// The first run time after `after`, before jitter.
+    pub fn next_after(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        match &self.every {
+            Every::Interval(secs) => after + Duration::seconds(*secs as i64),
+            // An expression no date matches, like "0 0 30 2 *", never runs.
+            Every::Cron(cron) => cron.next_after(after).unwrap_or(DateTime::<Utc>::MAX_UTC),
+        }
+    }
+}
+
+/// A five-field cron expression, `minute hour day-of-month month day-of-week`,
+/// evaluated in UTC. Fields take `*`, values, ranges `a-b`, steps `*/n` and
+/// `a-b/n`, and comma-separated lists; Sunday is 0 or 7. As in cron, when
+/// both day fields are restricted a day matching either one matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    expr: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+/// How far ahead a match is searched for, long enough for February 29th.
+const SEARCH_DAYS: i64 = 366 * 8;
+
+impl std::str::FromStr for Cron {
+    type Err = String;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("cron expression `{expr}` must have five fields"));
+        };
+        let weekdays = field(weekday, 0, 7, "day of week")?;
+        Ok(Cron {
+            expr: expr.to_string(),
+            minutes: field(minute, 0, 59, "minute")?,
+            hours: field(hour, 0, 23, "hour")?,
+            days: field(day, 1, 31, "day of month")?,
+            months: field(month, 1, 12, "month")?,
+            // Sunday is both 0 and 7.
+            weekdays: (weekdays | weekdays >> 7) & 0x7f,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+}
+
+/// Parses a cron field into a bit set of the values it matches.
+fn field(spec: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid cron {name} `{spec}`");
+    let mut set = 0;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (
+                    a.parse().map_err(|_| invalid())?,
+                    b.parse().map_err(|_| invalid())?,
+                ),
+                None => {
+                    let value = range.parse().map_err(|_| invalid())?;
+                    // "5/15" means from 5 to the end in steps of 15.
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+impl Cron {
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        self.months & (1 << date.month()) != 0
+            && match (self.any_day, self.any_weekday) {
+                (false, false) => day || weekday,
+                _ => day && weekday,
+            }
+    }
+
+    /// The first minute after `after` the expression matches, if one does
+    /// within the next eight years.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date_naive();
+        let (mut hour, mut minute) = (start.hour(), start.minute());
+        for _ in 0..SEARCH_DAYS {
+            if self.matches_day(date) {
+                for h in hour..24 {
+                    if self.hours & (1 << h) == 0 {
+                        continue;
+                    }
+                    let from = if h == hour { minute } else { 0 };
+                    if let Some(m) = (from..60).find(|m| self.minutes & (1 << m) != 0) {
+                        return Some(Utc.from_utc_datetime(&date.and_hms_opt(h, m, 0)?));
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+            (hour, minute) = (0, 0);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn next(expr: &str, after: &str) -> Option<DateTime<Utc>> {
+        expr.parse::<Cron>().unwrap().next_after(at(after))
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either_day() {
+        // The 1st of the month or a Monday; 2026-10-18 is a Sunday.
+        let expr = "0 0 1 * 1";
+        assert_eq!(
+            next(expr, "2026-10-18T12:00:00Z"),
+            Some(at("2026-10-19T00:00:00Z"))
+        );
+        assert_eq!(
+            next(expr, "2026-10-26T00:00:00Z"),
+            Some(at("2026-11-01T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn one_restricted_day_field_must_match() {
+        assert_eq!(
+            next("0 0 1 * *", "2026-10-18T12:00:00Z"),
+            Some(at("2026-11-01T00:00:00Z"))
+        );
+        assert_eq!(
+            next("0 0 * * 1", "2026-10-18T12:00:00Z"),
+            Some(at("2026-10-19T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn value_with_step_runs_to_the_end_of_the_range() {
+        let cron: Cron = "5/15 * * * *".parse().unwrap();
+        let minutes: Vec<u32> = (0..60).filter(|m| cron.minutes & (1 << m) != 0).collect();
+        assert_eq!(minutes, [5, 20, 35, 50]);
+        assert_eq!(
+            next("5/15 * * * *", "2026-10-18T12:20:00Z"),
+            Some(at("2026-10-18T12:35:00Z"))
+        );
+    }
+
+    #[test]
+    fn ranges_steps_and_lists_combine() {
+        let cron: Cron = "0 1-10/3,22 * * *".parse().unwrap();
+        let hours: Vec<u32> = (0..24).filter(|h| cron.hours & (1 << h) != 0).collect();
+        assert_eq!(hours, [1, 4, 7, 10, 22]);
+    }
+
+    #[test]
+    fn sunday_is_zero_or_seven() {
+        let sunday_zero: Cron = "0 12 * * 0".parse().unwrap();
+        let sunday_seven: Cron = "0 12 * * 7".parse().unwrap();
+        assert_eq!(sunday_zero.weekdays, sunday_seven.weekdays);
+        assert_eq!(
+            next("0 12 * * 7", "2026-10-18T12:00:00Z"),
+            Some(at("2026-10-25T12:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn next_run_is_strictly_after() {
+        assert_eq!(
+            next("30 * * * *", "2026-10-18T12:30:00Z"),
+            Some(at("2026-10-18T13:30:00Z"))
+        );
+        assert_eq!(
+            next("30 * * * *", "2026-10-18T12:29:59Z"),
+            Some(at("2026-10-18T12:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn leap_day_is_found() {
+        assert_eq!(
+            next("0 0 29 2 *", "2026-10-18T00:00:00Z"),
+            Some(at("2028-02-29T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn expression_matching_no_date_never_runs() {
+        assert_eq!(next("0 0 30 2 *", "2026-10-18T00:00:00Z"), None);
+        let schedule = Schedule {
+            every: Every::Cron("0 0 30 2 *".parse().unwrap()),
+            jitter_secs: 0,
+        };
+        assert_eq!(
+            schedule.next_after(at("2026-10-18T00:00:00Z")),
+            DateTime::<Utc>::MAX_UTC
+        );
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "10-5 * * * *",
+            "a * * * *",
+        ] {
+            assert!(expr.parse::<Cron>().is_err(), "{expr} should be rejected");
+        }
+    }
+
+    #[test]
+    fn schedule_needs_exactly_one_of_interval_and_cron() {
+        let parse = |interval_secs: Option<u64>, cron: Option<&str>| {
+            Schedule::try_from(ScheduleSpec {
+                interval_secs,
+                cron: cron.map(str::to_string),
+                jitter_secs: 0,
+            })
+        };
+        assert!(parse(Some(60), None).is_ok());
+        assert!(parse(None, Some("0 4 * * 1")).is_ok());
+        assert!(parse(Some(60), Some("0 4 * * 1")).is_err());
+        assert!(parse(None, None).is_err());
+        assert!(parse(Some(0), None).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use super::lifecycle::{Cycle, Lifecycle, cycle_key, version_parts};
+use super::product_cycle::{EolSource, ProductCycle};
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How long a product supports its release cycles, used to infer EOL dates
+/// that sources do not publish.
+///
+/// ```yaml
+/// support:
+///   rule: latest-lines
+///   lines: 2
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "rule", rename_all = "kebab-case")]
+pub enum SupportPolicy {
+    /// The newest `lines` cycles are supported; a cycle ends when the
+    /// `lines`-th newer one ships
+    LatestLines { lines: usize },
+    /// A cycle ends a fixed number of months after its first release
+    MonthsAfterRelease { months: u32 },
+    /// A cycle ends `months` after the next major version ships
+    NextMajor { months: u32 },
+    /// Even minors are stable lines, following `latest-lines`; odd minors are
+    /// development lines that end when the next minor ships
+    EvenMinorsStable {
+        #[serde(default = "default_lines")]
+        lines: usize,
+    },
+}
+
+fn default_lines() -> usize {
+    1
+}
+
+impl SupportPolicy {
+    /// The documented policy of products whose sources publish no EOL dates.
+    pub fn builtin(product: &str) -> Option<Self> {
+        match product {
+            // Each major Go release is supported until there are two newer ones.
+            "go" => Some(SupportPolicy::LatestLines { lines: 2 }),
+            // Perl supports its two latest stable (even) series.
+            "perl" => Some(SupportPolicy::EvenMinorsStable { lines: 2 }),
+            // nginx fixes the current stable (even) branch and mainline only.
+            "nginx" => Some(SupportPolicy::EvenMinorsStable { lines: 1 }),
+            // Ruby series get about three years of normal and security maintenance.
+            "ruby" => Some(SupportPolicy::MonthsAfterRelease { months: 39 }),
+            // Kong Gateway releases are supported for a year.
+            "kong" => Some(SupportPolicy::MonthsAfterRelease { months: 12 }),
+            // Docker Engine majors get fixes for about a month after the next major.
+            "docker" => Some(SupportPolicy::NextMajor { months: 1 }),
+            // Only the latest release line is maintained.
+            "caddy" => Some(SupportPolicy::LatestLines { lines: 1 }),
+            _ => None,
+        }
+    }
+
+    /// EOL dates the policy implies for each cycle, keyed by cycle name.
+    /// Cycles that are still supported are left out.
+    pub fn eol_dates(&self, lifecycle: &Lifecycle) -> HashMap<String, NaiveDate> {
+        // Cycles from oldest to newest.
+        let cycles: Vec<&Cycle> = lifecycle.cycles().iter().rev().collect();
+        let mut dates = HashMap::new();
+        match self {
+            SupportPolicy::LatestLines { lines } => {
+                dates.extend(superseded_after(&cycles, *lines));
+            }
+            SupportPolicy::MonthsAfterRelease { months } => {
+                for cycle in &cycles {
+                    if let Some(eol) = cycle
+                        .release_date
+                        .and_then(|d| d.checked_add_months(Months::new(*months)))
+                    {
+                        dates.insert(cycle.name.clone(), eol);
+                    }
+                }
+            }
+            SupportPolicy::NextMajor { months } => {
+                for cycle in &cycles {
+                    let major = version_parts(&cycle.name).first().copied();
+                    let next_major = cycles
+                        .iter()
+                        .filter(|c| version_parts(&c.name).first().copied() > major)
+                        .filter_map(|c| c.release_date)
+                        .min();
+                    if let Some(eol) =
+                        next_major.and_then(|d| d.checked_add_months(Months::new(*months)))
+                    {
+                        dates.insert(cycle.name.clone(), eol);
+                    }
+                }
+            }
+            SupportPolicy::EvenMinorsStable { lines } => {
+                let is_stable = |cycle: &Cycle| {
+                    version_parts(&cycle.name)
+                        .get(1)
+                        .is_some_and(|m| m % 2 == 0)
+                };
+                let (stable, development): (Vec<&Cycle>, Vec<&Cycle>) =
+                    cycles.iter().copied().partition(|c| is_stable(c));
+                dates.extend(superseded_after(&stable, *lines));
+                for cycle in development {
+                    let next = cycles
+                        .iter()
+                        .skip_while(|c| c.name != cycle.name)
+                        .nth(1)
+                        .and_then(|c| c.release_date);
+                    if let Some(eol) = next {
+                        dates.insert(cycle.name.clone(), eol);
+                    }
+                }
+            }
+        }
+        dates
+    }
+}
+
+/// Which release cycles of a product are long-term support releases, for
+/// sources that do not flag them.
+///
+/// ```yaml
+/// lts:
+///   rule: versions
+///   versions: ["8.0", "8.4"]
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "rule", rename_all = "kebab-case")]
+pub enum LtsRule {
+    /// Even-numbered majors, e.g. Node.js 18, 20 and 22
+    EvenMajors,
+    /// `YY.04` releases of even years, e.g. Ubuntu 22.04 and 24.04
+    EvenYearApril,
+    /// An explicit list of cycles; each entry also matches the versions it is a
+    /// prefix of, so "8.0" covers 8.0.36
+    Versions { versions: Vec<String> },
+}
+
+impl LtsRule {
+    /// The documented LTS scheme of products whose sources do not flag LTS releases.
+    pub fn builtin(product: &str) -> Option<Self> {
+        match product {
+            "node" => Some(LtsRule::EvenMajors),
+            "ubuntu" => Some(LtsRule::EvenYearApril),
+            "java" | "eclipse-temurin" => Some(LtsRule::Versions {
+                versions: ["8", "11", "17", "21", "25"].map(String::from).to_vec(),
+            }),
+            // MySQL 8.0 and 8.4 are LTS; the other 8.x and 9.x are Innovation releases.
+            "mysql" => Some(LtsRule::Versions {
+                versions: ["8.0", "8.4"].map(String::from).to_vec(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Whether the rule classifies a version as LTS.
+    pub fn matches(&self, version: &str) -> bool {
+        let parts = version_parts(version);
+        match self {
+            LtsRule::EvenMajors => parts.first().is_some_and(|major| major % 2 == 0),
+            LtsRule::EvenYearApril => {
+                matches!(parts.as_slice(), [year, 4, ..] if year % 2 == 0)
+            }
+            LtsRule::Versions { versions } => versions.iter().any(|v| {
+                let listed = version_parts(v);
+                !listed.is_empty() && parts.starts_with(&listed)
+            }),
+        }
+    }
+}
+
+/// Flags the rows an LTS rule classifies as LTS. Sources that flag LTS
+/// releases themselves take precedence: the rule only applies when no row is
+/// flagged, since Node.js majors, for one, only become LTS months after release.
+pub fn infer_lts(rows: &mut [ProductCycle], rule: &LtsRule) {
+    if rows.iter().any(|r| r.lts) {
+        return;
+    }
+    for row in rows.iter_mut() {
+        row.lts = rule.matches(&row.name);
+    }
+}
+
+/// For cycles sorted from oldest to newest, the first release of the cycle
+/// `lines` positions newer than each one.
+fn superseded_after(cycles: &[&Cycle], lines: usize) -> Vec<(String, NaiveDate)> {
+    cycles
+        .iter()
+        .zip(cycles.iter().skip(lines.max(1)))
+        .filter_map(|(cycle, newer)| Some((cycle.name.clone(), newer.release_date?)))
+        .collect()
+}
+
+/// Fills the missing EOL dates of collected rows from a support policy and
+/// marks them as inferred, returning how many rows were filled. Cycles with an
+/// official EOL date are left alone.
+pub fn infer_eol(product: &str, rows: &mut [ProductCycle], policy: &SupportPolicy) -> usize {
+    let lifecycle = Lifecycle::new(product, rows);
+    let official: Vec<&str> = lifecycle
+        .cycles()
+        .iter()
+        .filter(|c| c.eol_date.is_some())
+        .map(|c| c.name.as_str())
+        .collect();
+    let dates = policy.eol_dates(&lifecycle);
+
+    let mut inferred = 0;
+    for row in rows.iter_mut().filter(|r| r.eol_date.is_none()) {
+        let Some(key) = cycle_key(product, &row.name) else {
+            continue;
+        };
+        if official.contains(&key.as_str()) {
+            continue;
+        }
+        if let Some(eol) = dates.get(&key) {
+            row.eol_date = Some(*eol);
+            row.eol_source = EolSource::Inferred;
+            inferred += 1;
+        }
+    }
+    inferred
+}
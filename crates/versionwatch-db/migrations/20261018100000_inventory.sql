@@ -0,0 +1,17 @@
+-- Create inventory table
+-- This table stores the product versions actually deployed by each service.
+CREATE TABLE inventory (
+    id SERIAL PRIMARY KEY,
+    service VARCHAR(255) NOT NULL, -- e.g., "billing-api"
+    environment VARCHAR(255) NOT NULL, -- e.g., "production", "staging"
+    product VARCHAR(255) NOT NULL, -- e.g., "postgresql", must match a target name
+    current_version VARCHAR(255) NOT NULL, -- e.g., "16.2"
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(service, environment, product)
+);
+
+CREATE TRIGGER set_timestamp_inventory
+BEFORE UPDATE ON inventory
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
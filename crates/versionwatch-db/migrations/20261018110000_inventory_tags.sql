@@ -0,0 +1,3 @@
+-- Add tags to inventory entries
+-- Free-form labels lifecycle policies can be scoped by, e.g. "pci".
+ALTER TABLE inventory ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}';
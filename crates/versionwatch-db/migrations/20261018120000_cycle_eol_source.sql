@@ -0,0 +1,3 @@
+-- Record where each EOL date comes from
+-- "inferred" dates are derived from a product's support policy rather than published.
+ALTER TABLE cycles ADD COLUMN eol_source TEXT NOT NULL DEFAULT 'official';
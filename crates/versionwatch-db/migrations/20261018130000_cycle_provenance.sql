@@ -0,0 +1,3 @@
+-- Record where each collected row comes from
+-- Sources that listed the version, the source of each field and their conflicts.
+ALTER TABLE cycles ADD COLUMN provenance JSONB;
@@ -0,0 +1,15 @@
+-- Create quarantine table
+-- This table stores collected rows that failed validation, with the reason,
+-- so they can be reviewed instead of being stored as cycles.
+CREATE TABLE quarantine (
+    id SERIAL PRIMARY KEY,
+    product VARCHAR(255) NOT NULL, -- e.g., "mongodb", a target name
+    name VARCHAR(255) NOT NULL, -- the row's name as collected
+    release_date DATE,
+    eol_date DATE,
+    reason VARCHAR(64) NOT NULL, -- e.g., "eol_before_release"
+    detail TEXT NOT NULL,
+    quarantined_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_quarantine_product ON quarantine (product);
@@ -0,0 +1,10 @@
+-- Create run_baselines table
+-- This table stores a summary of the last accepted collection run of each
+-- product, which new runs are compared to before they replace its data.
+CREATE TABLE run_baselines (
+    product VARCHAR(255) PRIMARY KEY, -- a target name
+    row_count INTEGER NOT NULL,
+    release_dates INTEGER NOT NULL, -- rows with a release date
+    eol_dates INTEGER NOT NULL, -- rows with an EOL date
+    accepted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Flag rows served from a fallback dataset
+-- Curated known versions used when every live source failed.
+ALTER TABLE cycles ADD COLUMN fallback BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,18 @@
+-- Create collector_runs table
+-- This table stores every collection run of each target, for the dashboard
+-- metrics and their history.
+CREATE TABLE collector_runs (
+    id BIGSERIAL PRIMARY KEY,
+    target VARCHAR(255) NOT NULL, -- a target name
+    started_at TIMESTAMPTZ NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    status VARCHAR(32) NOT NULL, -- "active", "fallback", "degraded", "timeout" or "failed"
+    row_count INTEGER NOT NULL,
+    quarantined INTEGER NOT NULL DEFAULT 0,
+    error_kind VARCHAR(64), -- e.g., "rate_limited", "network"
+    error_message TEXT,
+    source VARCHAR(255), -- e.g., "github_tags+dockerhub"
+    UNIQUE (target, started_at)
+);
+
+CREATE INDEX idx_collector_runs_target ON collector_runs (target, started_at DESC);
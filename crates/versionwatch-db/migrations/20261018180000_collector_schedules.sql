@@ -0,0 +1,8 @@
+-- Create collector_schedules table
+-- This table stores when the dashboard next collects each target, so
+-- schedules carry over restarts.
+CREATE TABLE collector_schedules (
+    target VARCHAR(255) PRIMARY KEY,
+    next_run_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
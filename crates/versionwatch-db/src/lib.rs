@@ -1,4 +1,9 @@
-use versionwatch_core::domain::product_cycle::ProductCycle;
+use chrono::{DateTime, Utc};
+use versionwatch_core::domain::collector_run::{CollectorRun, RunStatus};
+use versionwatch_core::domain::inventory::InventoryEntry;
+use versionwatch_core::domain::product_cycle::{EolSource, ProductCycle};
+use versionwatch_core::domain::quarantine::{QuarantineReason, QuarantinedRow};
+use versionwatch_core::domain::regression::RunSummary;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -8,6 +13,36 @@ pub enum Error {
     Migrate(#[from] sqlx::migrate::MigrateError),
 }
 
+/// A `collector_runs` row.
+struct RunRow {
+    target: String,
+    started_at: DateTime<Utc>,
+    duration_ms: i64,
+    status: String,
+    row_count: i32,
+    quarantined: i32,
+    error_kind: Option<String>,
+    error_message: Option<String>,
+    source: Option<String>,
+}
+
+impl From<RunRow> for CollectorRun {
+    fn from(row: RunRow) -> Self {
+        CollectorRun {
+            target: row.target,
+            started_at: row.started_at,
+            duration_ms: row.duration_ms as u64,
+            status: RunStatus::from_label(&row.status),
+            rows: row.row_count as usize,
+            quarantined: row.quarantined as usize,
+            error_kind: row.error_kind,
+            error_message: row.error_message,
+            source: row.source,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Db {
     pool: sqlx::PgPool,
 }
@@ -36,27 +71,388 @@ impl Db {
         Ok(rec.id)
     }
 
-    pub async fn upsert_cycle(&self, product_id: i32, cycle: &ProductCycle) -> Result<(), Error> {
+    /// Inserts or updates a cycle row. Unless `replace_eol` is set, a stored
+    /// EOL date is kept when the row has none, so a source that stops
+    /// publishing EOL dates cannot erase them.
+    pub async fn upsert_cycle(
+        &self,
+        product_id: i32,
+        cycle: &ProductCycle,
+        replace_eol: bool,
+    ) -> Result<(), Error> {
+        let provenance = cycle
+            .provenance
+            .as_ref()
+            .and_then(|p| serde_json::to_value(p).ok());
         sqlx::query!(
             r#"
-            INSERT INTO cycles (product_id, name, release_date, eol_date, lts)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO cycles
+                (product_id, name, release_date, eol_date, lts, eol_source, provenance, fallback)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             ON CONFLICT (product_id, name) DO UPDATE
             SET
                 release_date = EXCLUDED.release_date,
-                eol_date = EXCLUDED.eol_date,
+                eol_date = CASE WHEN $9 THEN EXCLUDED.eol_date
+                    ELSE COALESCE(EXCLUDED.eol_date, cycles.eol_date) END,
                 lts = EXCLUDED.lts,
+                eol_source = CASE WHEN $9 OR EXCLUDED.eol_date IS NOT NULL
+                    THEN EXCLUDED.eol_source ELSE cycles.eol_source END,
+                provenance = EXCLUDED.provenance,
+                fallback = EXCLUDED.fallback,
                 updated_at = NOW()
             "#,
             product_id,
             cycle.name,
             cycle.release_date,
             cycle.eol_date,
-            cycle.lts
+            cycle.lts,
+            cycle.eol_source.label(),
+            provenance,
+            cycle.fallback,
+            replace_eol
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stores the rows collected for a product, creating the product if needed.
+    /// Stored EOL dates the rows leave out are kept.
+    pub async fn store_cycles(&self, product: &str, cycles: &[ProductCycle]) -> Result<(), Error> {
+        let product_id = self.upsert_product(product).await?;
+        for cycle in cycles {
+            self.upsert_cycle(product_id, cycle, false).await?;
+        }
+        Ok(())
+    }
+
+    /// Stores the rows of a product as they are, clearing stored EOL dates the
+    /// rows leave out, for runs acknowledged after their source degraded and
+    /// for imported bundles.
+    pub async fn replace_cycles(
+        &self,
+        product: &str,
+        cycles: &[ProductCycle],
+    ) -> Result<(), Error> {
+        let product_id = self.upsert_product(product).await?;
+        for cycle in cycles {
+            self.upsert_cycle(product_id, cycle, true).await?;
+        }
+        Ok(())
+    }
+
+    /// Every stored cycle row with the name of its product, ordered by product.
+    pub async fn list_cycles(&self) -> Result<Vec<(String, ProductCycle)>, Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT p.name AS product, c.name, c.release_date, c.eol_date, c.lts, c.eol_source,
+                c.provenance, c.fallback
+            FROM cycles c
+            JOIN products p ON p.id = c.product_id
+            ORDER BY p.name, c.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.product,
+                    ProductCycle {
+                        name: row.name,
+                        release_date: row.release_date,
+                        eol_date: row.eol_date,
+                        lts: row.lts.unwrap_or(false),
+                        eol_source: EolSource::from_label(&row.eol_source),
+                        provenance: row
+                            .provenance
+                            .and_then(|json| serde_json::from_value(json).ok()),
+                        fallback: row.fallback,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Replaces the quarantined rows of a product with those of its latest run.
+    pub async fn store_quarantine(
+        &self,
+        product: &str,
+        rows: &[QuarantinedRow],
+    ) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!("DELETE FROM quarantine WHERE product = $1", product)
+            .execute(&mut *tx)
+            .await?;
+        for row in rows {
+            sqlx::query!(
+                r#"
+                INSERT INTO quarantine
+                    (product, name, release_date, eol_date, reason, detail, quarantined_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+                row.product,
+                row.name,
+                row.release_date,
+                row.eol_date,
+                row.reason.label(),
+                row.detail,
+                row.quarantined_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Every quarantined row, ordered by product.
+    pub async fn list_quarantine(&self) -> Result<Vec<QuarantinedRow>, Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT product, name, release_date, eol_date, reason, detail, quarantined_at
+            FROM quarantine
+            ORDER BY product, id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(QuarantinedRow {
+                    product: row.product,
+                    name: row.name,
+                    release_date: row.release_date,
+                    eol_date: row.eol_date,
+                    reason: QuarantineReason::from_label(&row.reason)?,
+                    detail: row.detail,
+                    quarantined_at: row.quarantined_at,
+                })
+            })
+            .collect())
+    }
+
+    /// Records a product's run as the last accepted one.
+    pub async fn store_baseline(&self, product: &str, summary: &RunSummary) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO run_baselines (product, row_count, release_dates, eol_dates)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (product) DO UPDATE
+            SET
+                row_count = EXCLUDED.row_count,
+                release_dates = EXCLUDED.release_dates,
+                eol_dates = EXCLUDED.eol_dates,
+                accepted_at = NOW()
+            "#,
+            product,
+            summary.rows as i32,
+            summary.release_dates as i32,
+            summary.eol_dates as i32
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The last accepted run of each product.
+    pub async fn list_baselines(&self) -> Result<Vec<(String, RunSummary)>, Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT product, row_count, release_dates, eol_dates
+            FROM run_baselines
+            ORDER BY product
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let summary = RunSummary {
+                    rows: row.row_count as usize,
+                    release_dates: row.release_dates as usize,
+                    eol_dates: row.eol_dates as usize,
+                };
+                (row.product, summary)
+            })
+            .collect())
+    }
+
+    /// Records a run, unless the target already has a run that started at the
+    /// same millisecond, so importing a bundle twice adds its runs once.
+    /// Returns whether the run was added.
+    pub async fn record_run(&self, run: &CollectorRun) -> Result<bool, Error> {
+        // Bundles keep start times to the millisecond.
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO collector_runs
+                (target, started_at, duration_ms, status, row_count, quarantined, error_kind,
+                 error_message, source)
+            VALUES ($1, date_trunc('milliseconds', $2::timestamptz), $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (target, started_at) DO NOTHING
+            "#,
+            run.target,
+            run.started_at,
+            run.duration_ms as i64,
+            run.status.label(),
+            run.rows as i32,
+            run.quarantined as i32,
+            run.error_kind,
+            run.error_message,
+            run.source
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The latest run of each target.
+    pub async fn latest_runs(&self) -> Result<Vec<CollectorRun>, Error> {
+        let rows = sqlx::query_as!(
+            RunRow,
+            r#"
+            SELECT DISTINCT ON (target)
+                target, started_at, duration_ms, status, row_count, quarantined, error_kind,
+                error_message, source
+            FROM collector_runs
+            ORDER BY target, started_at DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(CollectorRun::from).collect())
+    }
+
+    /// The latest `limit` runs of a target, newest first.
+    pub async fn list_runs(&self, target: &str, limit: i64) -> Result<Vec<CollectorRun>, Error> {
+        let rows = sqlx::query_as!(
+            RunRow,
+            r#"
+            SELECT target, started_at, duration_ms, status, row_count, quarantined, error_kind,
+                error_message, source
+            FROM collector_runs
+            WHERE target = $1
+            ORDER BY started_at DESC
+            LIMIT $2
+            "#,
+            target,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(CollectorRun::from).collect())
+    }
+
+    /// Sets the status of a target's latest run, once its held-back data was
+    /// acknowledged.
+    pub async fn update_latest_run(
+        &self,
+        target: &str,
+        status: RunStatus,
+        error_message: Option<&str>,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+            UPDATE collector_runs
+            SET status = $2, error_kind = NULL, error_message = $3
+            WHERE id = (
+                SELECT id FROM collector_runs
+                WHERE target = $1
+                ORDER BY started_at DESC
+                LIMIT 1
+            )
+            "#,
+            target,
+            status.label(),
+            error_message
         )
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
+
+    pub async fn store_next_run(&self, target: &str, at: DateTime<Utc>) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO collector_schedules (target, next_run_at)
+            VALUES ($1, $2)
+            ON CONFLICT (target) DO UPDATE
+            SET next_run_at = EXCLUDED.next_run_at, updated_at = NOW()
+            "#,
+            target,
+            at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// When each target is next collected.
+    pub async fn list_next_runs(&self) -> Result<Vec<(String, DateTime<Utc>)>, Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT target, next_run_at
+            FROM collector_schedules
+            ORDER BY target
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.target, row.next_run_at))
+            .collect())
+    }
+
+    pub async fn upsert_inventory_entry(&self, entry: &InventoryEntry) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO inventory (service, environment, product, current_version, tags)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (service, environment, product) DO UPDATE
+            SET
+                current_version = EXCLUDED.current_version,
+                tags = EXCLUDED.tags,
+                updated_at = NOW()
+            "#,
+            entry.service,
+            entry.environment,
+            entry.product,
+            entry.current_version,
+            &entry.tags
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_inventory(&self) -> Result<Vec<InventoryEntry>, Error> {
+        let entries = sqlx::query_as!(
+            InventoryEntry,
+            r#"
+            SELECT service, environment, product, current_version, tags
+            FROM inventory
+            ORDER BY service, environment, product
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
 }
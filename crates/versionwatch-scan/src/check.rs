@@ -0,0 +1,309 @@
+use crate::escape_xml;
+use crate::report::{FindingReport, ScanStatus};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use versionwatch_core::domain::lifecycle::Lifecycle;
+use versionwatch_core::domain::policy::{Policy, Subject, Waiver};
+use versionwatch_core::domain::product_cycle::ProductCycle;
+
+pub use versionwatch_core::domain::policy::Severity;
+
+/// A scanned reference and the verdict of the policy on it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckResult {
+    #[serde(flatten)]
+    pub report: FindingReport,
+    pub severity: Severity,
+    /// Identifier of the rule behind the verdict, e.g. "eol"
+    pub rule: Option<String>,
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    /// Policy exception waiving the rule, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiver: Option<Waiver>,
+}
+
+/// The built-in policy: EOL fails, and cycles near EOL and references behind
+/// their cycle's latest patch warn.
+pub fn evaluate(reports: Vec<FindingReport>) -> Vec<CheckResult> {
+    reports
+        .into_iter()
+        .map(|report| {
+            let subject = subject(&report);
+            let (severity, rule, message) = match report.status {
+                ScanStatus::Eol => (
+                    Severity::Fail,
+                    "eol",
+                    format!("{subject} is end of life{}", eol_suffix(&report)),
+                ),
+                ScanStatus::NearEol => (
+                    Severity::Warn,
+                    "near-eol",
+                    format!("{subject} reaches end of life{}", eol_suffix(&report)),
+                ),
+                _ if report.outdated => (
+                    Severity::Warn,
+                    "outdated",
+                    format!(
+                        "{} is behind {}",
+                        report.finding.reference,
+                        report.latest.as_deref().unwrap_or("the latest release")
+                    ),
+                ),
+                _ => {
+                    return CheckResult {
+                        report,
+                        severity: Severity::Pass,
+                        rule: None,
+                        message: None,
+                        remediation: None,
+                        waiver: None,
+                    };
+                }
+            };
+            CheckResult {
+                report,
+                severity,
+                rule: Some(rule.to_string()),
+                message: Some(message),
+                remediation: None,
+                waiver: None,
+            }
+        })
+        .collect()
+}
+
+/// Where a scan runs, for policy rules scoped by environment or tag.
+#[derive(Debug, Clone, Default)]
+pub struct PolicyContext {
+    pub environment: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// Evaluates reports against a lifecycle policy instead of the built-in one,
+/// with one result per violation and one passing result per compliant reference.
+///
+/// References the policy cannot judge, such as runner images, which have no
+/// collected cycles, keep the verdict of the built-in policy.
+pub fn evaluate_policy(
+    reports: Vec<FindingReport>,
+    policy: &Policy,
+    cycles: &HashMap<String, Vec<ProductCycle>>,
+    context: &PolicyContext,
+    today: NaiveDate,
+) -> Vec<CheckResult> {
+    let mut lifecycles: HashMap<String, Lifecycle> = HashMap::new();
+    let mut results = Vec::new();
+
+    for report in reports {
+        let finding = &report.finding;
+        let (Some(product), Some(version), Some(rows)) = (
+            finding.product.as_deref(),
+            finding.version.as_deref(),
+            finding.product.as_ref().and_then(|p| cycles.get(p)),
+        ) else {
+            results.extend(evaluate(vec![report]));
+            continue;
+        };
+        let lifecycle = lifecycles
+            .entry(product.to_string())
+            .or_insert_with(|| Lifecycle::new(product, rows));
+
+        let name = format!("{}:{}", uri(&report), finding.line);
+        let subject = Subject {
+            name: &name,
+            product,
+            version,
+            environment: context.environment.as_deref(),
+            tags: &context.tags,
+        };
+        let violations = policy.evaluate(&subject, lifecycle, today);
+        if violations.is_empty() {
+            results.push(CheckResult {
+                report,
+                severity: Severity::Pass,
+                rule: None,
+                message: None,
+                remediation: None,
+                waiver: None,
+            });
+            continue;
+        }
+        for violation in violations {
+            results.push(CheckResult {
+                report: report.clone(),
+                severity: violation.effective_severity(),
+                rule: Some(violation.rule),
+                message: Some(violation.message),
+                remediation: Some(violation.remediation),
+                waiver: violation.waiver,
+            });
+        }
+    }
+
+    results
+}
+
+/// The worst severity among the results.
+pub fn overall(results: &[CheckResult]) -> Severity {
+    results
+        .iter()
+        .map(|r| r.severity)
+        .max()
+        .unwrap_or(Severity::Pass)
+}
+
+fn subject(report: &FindingReport) -> String {
+    match (&report.finding.product, &report.cycle) {
+        (Some(product), Some(cycle)) => format!("{product} {cycle}"),
+        _ => report.finding.reference.clone(),
+    }
+}
+
+fn eol_suffix(report: &FindingReport) -> String {
+    report
+        .eol_date
+        .map(|eol| format!(" ({eol})"))
+        .unwrap_or_default()
+}
+
+/// The message followed by the remediation, when the policy gives one.
+fn full_message(result: &CheckResult) -> String {
+    let message = result.message.as_deref().unwrap_or_default();
+    match &result.remediation {
+        Some(remediation) => format!("{message}. {remediation}"),
+        None => message.to_string(),
+    }
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Pass => "none",
+        Severity::Warn => "warning",
+        Severity::Fail => "error",
+    }
+}
+
+fn rule_description(rule: &str) -> String {
+    match rule {
+        "eol" => "Cycle is end of life".to_string(),
+        "near-eol" => "Cycle reaches end of life soon".to_string(),
+        "outdated" => "A newer patch release is available".to_string(),
+        rule => rule.replace('-', " "),
+    }
+}
+
+fn uri(report: &FindingReport) -> String {
+    let path = report.finding.file.to_string_lossy().replace('\\', "/");
+    path.trim_start_matches("./").to_string()
+}
+
+/// Renders results as a SARIF 2.1.0 log for code scanning. Passing results are left out.
+pub fn sarif(results: &[CheckResult]) -> Value {
+    let mut rules: BTreeMap<&str, Severity> = BTreeMap::new();
+    for result in results.iter().filter(|r| r.severity != Severity::Pass) {
+        if let Some(rule) = &result.rule {
+            rules.entry(rule).or_insert(result.severity);
+        }
+    }
+
+    let sarif_results: Vec<Value> = results
+        .iter()
+        .filter(|r| r.severity != Severity::Pass)
+        .map(|r| {
+            json!({
+                "ruleId": r.rule,
+                "level": sarif_level(r.severity),
+                "message": { "text": full_message(r) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri(&r.report) },
+                        "region": { "startLine": r.report.finding.line },
+                    }
+                }],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "versionwatch",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules
+                        .iter()
+                        .map(|(id, severity)| json!({
+                            "id": id,
+                            "shortDescription": { "text": rule_description(id) },
+                            "defaultConfiguration": { "level": sarif_level(*severity) },
+                        }))
+                        .collect::<Vec<_>>(),
+                }
+            },
+            "results": sarif_results,
+        }]
+    })
+}
+
+/// Renders results as a JUnit XML report, one test suite per scanned file and
+/// one test case per reference. Failures fail the test case; warnings are
+/// reported in its output.
+pub fn junit(results: &[CheckResult]) -> String {
+    let mut suites: BTreeMap<String, Vec<&CheckResult>> = BTreeMap::new();
+    for result in results {
+        suites.entry(uri(&result.report)).or_default().push(result);
+    }
+    let failures = |results: &[&CheckResult]| {
+        results
+            .iter()
+            .filter(|r| r.severity == Severity::Fail)
+            .count()
+    };
+    let all: Vec<&CheckResult> = results.iter().collect();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"versionwatch\" tests=\"{}\" failures=\"{}\">\n",
+        results.len(),
+        failures(&all)
+    ));
+    for (file, results) in &suites {
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+            escape_xml(file),
+            results.len(),
+            failures(results)
+        ));
+        for result in results {
+            let finding = &result.report.finding;
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                escape_xml(file),
+                escape_xml(&format!(
+                    "{} ({} line {})",
+                    finding.reference, finding.source, finding.line
+                ))
+            ));
+            let message = escape_xml(&full_message(result));
+            match result.severity {
+                Severity::Pass => xml.push_str("/>\n"),
+                Severity::Warn => xml.push_str(&format!(
+                    ">\n      <system-out>warning: {message}</system-out>\n    </testcase>\n"
+                )),
+                Severity::Fail => xml.push_str(&format!(
+                    ">\n      <failure message=\"{message}\" type=\"{}\"/>\n    </testcase>\n",
+                    escape_xml(result.rule.as_deref().unwrap_or_default())
+                )),
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
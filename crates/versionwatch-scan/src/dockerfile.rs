@@ -0,0 +1,133 @@
+use crate::images::image_finding;
+use crate::{Error, Finding, read_file};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Tells whether a file name looks like a Dockerfile.
+pub fn is_dockerfile(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    name == "Dockerfile"
+        || name == "Containerfile"
+        || name.starts_with("Dockerfile.")
+        || name.to_ascii_lowercase().ends_with(".dockerfile")
+}
+
+/// Scans a Dockerfile for base images. `build_args` override `ARG` defaults,
+/// like `docker build --build-arg` does.
+pub fn scan_file(path: &Path, build_args: &HashMap<String, String>) -> Result<Vec<Finding>, Error> {
+    let contents = read_file(path)?;
+    Ok(parse(path, &contents, build_args))
+}
+
+/// Extracts one finding per `FROM` instruction.
+///
+/// Global `ARG`s (declared before the first `FROM`) are substituted into image
+/// references, and `FROM` lines that refer to an earlier build stage are skipped.
+pub fn parse(path: &Path, contents: &str, build_args: &HashMap<String, String>) -> Vec<Finding> {
+    let mut args: HashMap<String, String> = HashMap::new();
+    let mut stages: Vec<String> = Vec::new();
+    let mut seen_from = false;
+    let mut findings = Vec::new();
+
+    for (line, instruction) in logical_lines(contents) {
+        let mut words = instruction.split_whitespace();
+        let Some(keyword) = words.next() else {
+            continue;
+        };
+
+        match keyword.to_ascii_uppercase().as_str() {
+            "ARG" if !seen_from => {
+                for declaration in words {
+                    let (name, default) = match declaration.split_once('=') {
+                        Some((name, default)) => (name, Some(unquote(default))),
+                        None => (declaration, None),
+                    };
+                    if let Some(value) = build_args.get(name).cloned().or(default) {
+                        args.insert(name.to_string(), value);
+                    }
+                }
+            }
+            "FROM" => {
+                seen_from = true;
+                let operands: Vec<&str> = words.filter(|w| !w.starts_with("--")).collect();
+                let Some(reference) = operands.first() else {
+                    continue;
+                };
+                let image = substitute(reference, &args);
+                let is_stage = stages.contains(&image.to_lowercase());
+
+                if operands.len() >= 3 && operands[1].eq_ignore_ascii_case("as") {
+                    stages.push(operands[2].to_lowercase());
+                }
+                if is_stage || image.eq_ignore_ascii_case("scratch") {
+                    continue;
+                }
+
+                findings.push(image_finding(path, line, "FROM", &image));
+            }
+            _ => {}
+        }
+    }
+
+    findings
+}
+
+/// Joins continuation lines and drops comments, keeping the line number each
+/// instruction starts on.
+fn logical_lines(contents: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (index, raw) in contents.lines().enumerate() {
+        let trimmed = raw.trim();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (text, continues) = match trimmed.strip_suffix('\\') {
+            Some(text) => (text, true),
+            None => (trimmed, false),
+        };
+
+        let (_, buffer) = current.get_or_insert_with(|| (index + 1, String::new()));
+        buffer.push(' ');
+        buffer.push_str(text);
+
+        if !continues {
+            lines.extend(current.take());
+        }
+    }
+    lines.extend(current);
+
+    lines
+        .into_iter()
+        .map(|(line, text)| (line, text.trim().to_string()))
+        .filter(|(_, text)| !text.is_empty())
+        .collect()
+}
+
+/// Expands `$VAR`, `${VAR}`, `${VAR:-default}` and `${VAR:+alternative}`.
+fn substitute(text: &str, args: &HashMap<String, String>) -> String {
+    let re =
+        Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(?::([-+])([^}]*))?\}|\$([A-Za-z_][A-Za-z0-9_]*)")
+            .unwrap();
+
+    re.replace_all(text, |caps: &Captures| {
+        let name = caps.get(1).or(caps.get(4)).unwrap().as_str();
+        let value = args.get(name).filter(|v| !v.is_empty());
+        match (caps.get(2).map(|m| m.as_str()), value) {
+            (Some("-"), None) => caps[3].to_string(),
+            (Some("+"), Some(_)) => caps[3].to_string(),
+            (Some("+"), None) => String::new(),
+            (_, value) => value.cloned().unwrap_or_default(),
+        }
+    })
+    .into_owned()
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches(|c| c == '"' || c == '\'').to_string()
+}
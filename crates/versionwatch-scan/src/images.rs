@@ -0,0 +1,130 @@
+use crate::Finding;
+use std::path::Path;
+
+/// Container image repositories and the tracked product they ship.
+///
+/// Official images are matched on their bare name; a few well-known publishers
+/// repackage the same products under their own namespace.
+const IMAGE_PRODUCTS: &[(&str, &str)] = &[
+    ("caddy", "caddy"),
+    ("docker", "docker"),
+    ("eclipse-temurin", "eclipse-temurin"),
+    ("golang", "go"),
+    ("httpd", "apache"),
+    ("kong", "kong"),
+    ("mongo", "mongodb"),
+    ("mysql", "mysql"),
+    ("nginx", "nginx"),
+    ("node", "node"),
+    ("perl", "perl"),
+    ("php", "php"),
+    ("postgres", "postgresql"),
+    ("python", "python"),
+    ("ruby", "ruby"),
+    ("rust", "rust"),
+    ("swift", "swift"),
+    ("bitnami/mongodb", "mongodb"),
+    ("bitnami/mysql", "mysql"),
+    ("bitnami/nginx", "nginx"),
+    ("bitnami/node", "node"),
+    ("bitnami/postgresql", "postgresql"),
+    ("bitnami/python", "python"),
+    ("nginxinc/nginx-unprivileged", "nginx"),
+];
+
+/// Registries and namespaces that mirror Docker Hub official images.
+const OFFICIAL_PREFIXES: &[&str] = &[
+    "docker.io/library/",
+    "docker.io/",
+    "index.docker.io/library/",
+    "library/",
+    "public.ecr.aws/docker/library/",
+    "mirror.gcr.io/library/",
+];
+
+/// A parsed image reference such as `docker.io/library/node:18-alpine@sha256:...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageReference {
+    /// Repository without registry for Docker Hub images, e.g. "node" or "bitnami/node"
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+impl ImageReference {
+    pub fn parse(reference: &str) -> Option<Self> {
+        let reference = reference.trim();
+        if reference.is_empty() || reference.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let (name, digest) = match reference.split_once('@') {
+            Some((name, digest)) => (name, Some(digest.to_string())),
+            None => (reference, None),
+        };
+
+        // A colon after the last slash separates the tag; earlier ones belong to a registry port.
+        let last_slash = name.rfind('/').map_or(0, |i| i + 1);
+        let (repository, tag) = match name[last_slash..].find(':') {
+            Some(i) => (&name[..last_slash + i], Some(&name[last_slash + i + 1..])),
+            None => (name, None),
+        };
+
+        let repository = OFFICIAL_PREFIXES
+            .iter()
+            .find_map(|prefix| repository.strip_prefix(prefix))
+            .unwrap_or(repository);
+
+        Some(Self {
+            repository: repository.to_lowercase(),
+            tag: tag.map(str::to_string),
+            digest,
+        })
+    }
+
+    /// The tracked product shipped by this image, if any.
+    pub fn product(&self) -> Option<&'static str> {
+        IMAGE_PRODUCTS
+            .iter()
+            .find(|(repository, _)| *repository == self.repository)
+            .map(|(_, product)| *product)
+    }
+
+    /// Splits the tag into a version prefix and a variant suffix,
+    /// e.g. "3.8-slim" into ("3.8", "-slim"). Tags without a version yield `None`.
+    pub fn version(&self) -> Option<(String, String)> {
+        split_version(self.tag.as_deref()?)
+    }
+}
+
+/// Splits a tag such as "17.0.2_8-jdk" into its version ("17.0.2") and variant ("_8-jdk").
+pub fn split_version(tag: &str) -> Option<(String, String)> {
+    let tag = tag.strip_prefix('v').unwrap_or(tag);
+    let end = tag
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(tag.len());
+    let version = tag[..end].trim_end_matches('.');
+    if version.is_empty() {
+        return None;
+    }
+    Some((version.to_string(), tag[version.len()..].to_string()))
+}
+
+/// Builds a finding for an image reference found in a file.
+pub(crate) fn image_finding(file: &Path, line: usize, source: &str, reference: &str) -> Finding {
+    let image = ImageReference::parse(reference);
+    let product = image.as_ref().and_then(|i| i.product());
+    let version = image.as_ref().and_then(|i| i.version());
+
+    Finding {
+        file: file.to_path_buf(),
+        line,
+        source: source.to_string(),
+        reference: reference.to_string(),
+        product: product.map(str::to_string),
+        version: version.as_ref().map(|(v, _)| v.clone()),
+        variant: version
+            .map(|(_, variant)| variant)
+            .filter(|variant| !variant.is_empty()),
+    }
+}
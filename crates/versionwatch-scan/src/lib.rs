@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+pub mod check;
+pub mod dockerfile;
+pub mod images;
+pub mod lockfiles;
+pub mod manifests;
+pub mod report;
+pub mod runtimes;
+pub mod sbom;
+pub mod tool_versions;
+pub mod workflows;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("could not read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("could not parse {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+}
+
+/// A product version referenced somewhere in a repository.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Finding {
+    pub file: PathBuf,
+    /// 1-based line number of the reference
+    pub line: usize,
+    /// What declared the version, e.g. "FROM"
+    pub source: String,
+    /// The reference as written, e.g. "node:18-alpine"
+    pub reference: String,
+    /// Tracked product the reference maps to, if any
+    pub product: Option<String>,
+    /// Version or version prefix extracted from the reference
+    pub version: Option<String>,
+    /// Variant suffix to carry over when suggesting a newer tag, e.g. "-alpine"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+}
+
+pub(crate) fn read_file(path: &Path) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|source| Error::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+pub(crate) fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 1-based line of the first line containing `needle`, or 1 if none does.
+pub(crate) fn line_of(contents: &str, needle: &str) -> usize {
+    contents
+        .lines()
+        .position(|line| line.contains(needle))
+        .map_or(1, |index| index + 1)
+}
+
+/// Locates values in the raw text, since parsed YAML carries no positions.
+pub(crate) struct LineIndex<'a> {
+    lines: Vec<&'a str>,
+    cursor: usize,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(crate) fn new(contents: &'a str) -> Self {
+        Self {
+            lines: contents.lines().collect(),
+            cursor: 0,
+        }
+    }
+
+    /// Returns the 1-based line of the next occurrence of `needle`, searching
+    /// forward from the previous match so repeated values get distinct lines.
+    pub(crate) fn find(&mut self, needle: &str) -> usize {
+        let found = (self.cursor..self.lines.len())
+            .chain(0..self.cursor)
+            .find(|&i| self.lines[i].contains(needle));
+
+        match found {
+            Some(index) => {
+                self.cursor = index + 1;
+                index + 1
+            }
+            None => self.cursor.max(1),
+        }
+    }
+
+    /// Returns the 1-based line of the next `key: value` entry, quoted or not,
+    /// searching forward from the previous match like [`LineIndex::find`].
+    pub(crate) fn find_entry(&mut self, key: &str, value: &str) -> Option<usize> {
+        let index = (self.cursor..self.lines.len())
+            .chain(0..self.cursor)
+            .find(|&i| raw_entry(self.lines[i], key).as_deref() == Some(value))?;
+        self.cursor = index + 1;
+        Some(index + 1)
+    }
+
+    /// Reads the raw scalar of `key:` among the entries that follow a 1-based
+    /// line in the same mapping, so a sibling block's key is never picked up.
+    pub(crate) fn raw_value_after(&self, line: usize, key: &str) -> Option<String> {
+        let indent = key_indent(self.lines.get(line.checked_sub(1)?)?);
+        for l in self.lines.iter().skip(line) {
+            let trimmed = l.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let l_indent = key_indent(l);
+            if l_indent < indent || (l_indent == indent && trimmed.starts_with('-')) {
+                return None;
+            }
+            let value = (l_indent == indent).then(|| raw_entry(l, key)).flatten();
+            if value.is_some() {
+                return value;
+            }
+        }
+        None
+    }
+}
+
+/// Column a line's key starts at, past any sequence dash.
+fn key_indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '-']).len()
+}
+
+/// The raw scalar of a `key: value` line, without quotes or a trailing comment.
+fn raw_entry(line: &str, key: &str) -> Option<String> {
+    let entry = line.trim().trim_start_matches(['-', ' ']);
+    let value = entry.strip_prefix(key)?.strip_prefix(':')?;
+    let value = value.split(" #").next().unwrap_or_default().trim();
+    Some(value.trim_matches(|c| c == '"' || c == '\'').to_string())
+}
+
+/// Directories that never hold files worth scanning.
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target", "vendor", ".venv"];
+
+/// Expands paths into the files they contain that satisfy `matches`.
+///
+/// Files given explicitly are kept as-is; directories are walked recursively.
+pub fn find_files(paths: &[PathBuf], matches: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending: Vec<PathBuf> = paths.to_vec();
+
+    while let Some(path) = pending.pop() {
+        if !path.is_dir() {
+            files.push(path);
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(&path) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            if path.is_dir() {
+                if !SKIPPED_DIRS.iter().any(|skipped| name == *skipped) {
+                    pending.push(path);
+                }
+            } else if matches(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
@@ -0,0 +1,305 @@
+use crate::{Error, LineIndex, read_file};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use versionwatch_core::domain::dependency::{Dependency, Ecosystem};
+use versionwatch_core::domain::lifecycle::compare_versions;
+
+/// Lockfiles and the ecosystem whose packages they pin.
+const LOCKFILES: &[(&str, Ecosystem)] = &[
+    ("Cargo.lock", Ecosystem::Cargo),
+    ("package-lock.json", Ecosystem::Npm),
+    ("pnpm-lock.yaml", Ecosystem::Npm),
+    ("yarn.lock", Ecosystem::Npm),
+    ("poetry.lock", Ecosystem::PyPi),
+    ("Gemfile.lock", Ecosystem::RubyGems),
+    ("composer.lock", Ecosystem::Packagist),
+    ("go.sum", Ecosystem::Go),
+];
+
+/// A dependency together with where it is locked.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LockedDependency {
+    pub file: PathBuf,
+    /// 1-based line number of the entry
+    pub line: usize,
+    #[serde(flatten)]
+    pub dependency: Dependency,
+}
+
+/// Tells whether a file is a supported lockfile.
+pub fn is_lockfile(path: &Path) -> bool {
+    lockfile_ecosystem(path).is_some()
+}
+
+fn lockfile_ecosystem(path: &Path) -> Option<Ecosystem> {
+    let name = path.file_name()?.to_str()?;
+    LOCKFILES
+        .iter()
+        .find(|(lockfile, _)| *lockfile == name)
+        .map(|(_, ecosystem)| *ecosystem)
+}
+
+/// Reads the registry packages pinned by a lockfile.
+pub fn scan_file(path: &Path) -> Result<Vec<LockedDependency>, Error> {
+    let contents = read_file(path)?;
+    parse(path, &contents)
+}
+
+/// Extracts registry packages from a lockfile, picking the format from its name.
+///
+/// Workspace members, path and git dependencies are skipped since no registry
+/// knows about them. Each name and version is reported once.
+pub fn parse(path: &Path, contents: &str) -> Result<Vec<LockedDependency>, Error> {
+    let parse_error = |message: String| Error::Parse {
+        path: path.to_path_buf(),
+        message,
+    };
+    let Some(ecosystem) = lockfile_ecosystem(path) else {
+        return Ok(Vec::new());
+    };
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+
+    // (name, version, text to locate the entry by)
+    let packages: Vec<(String, String, String)> = match name {
+        "Cargo.lock" | "poetry.lock" => {
+            let document: toml::Table =
+                contents.parse().map_err(|e| parse_error(format!("{e}")))?;
+            let entries = document.get("package").and_then(|p| p.as_array());
+            entries
+                .into_iter()
+                .flatten()
+                .filter(|package| {
+                    // Cargo marks registry packages with a `source`; local ones have none.
+                    name != "Cargo.lock"
+                        || package
+                            .get("source")
+                            .and_then(|s| s.as_str())
+                            .is_some_and(|s| s.starts_with("registry+") || s.starts_with("sparse+"))
+                })
+                .filter_map(|package| {
+                    let name = package.get("name")?.as_str()?;
+                    let version = package.get("version")?.as_str()?;
+                    Some((
+                        name.to_string(),
+                        version.to_string(),
+                        format!("name = \"{name}\""),
+                    ))
+                })
+                .collect()
+        }
+        "package-lock.json" => {
+            let document: Value =
+                serde_json::from_str(contents).map_err(|e| parse_error(format!("{e}")))?;
+            npm_packages(&document)
+        }
+        "composer.lock" => {
+            let document: Value =
+                serde_json::from_str(contents).map_err(|e| parse_error(format!("{e}")))?;
+            ["packages", "packages-dev"]
+                .iter()
+                .filter_map(|key| document.get(key).and_then(Value::as_array))
+                .flatten()
+                .filter_map(|package| {
+                    let name = package.get("name")?.as_str()?;
+                    let version = package.get("version")?.as_str()?;
+                    Some((name.to_string(), version.to_string(), format!("\"{name}\"")))
+                })
+                .collect()
+        }
+        "pnpm-lock.yaml" => {
+            let document: serde_yaml::Value =
+                serde_yaml::from_str(contents).map_err(|e| parse_error(format!("{e}")))?;
+            document
+                .get("packages")
+                .and_then(serde_yaml::Value::as_mapping)
+                .into_iter()
+                .flatten()
+                .filter_map(|(key, _)| {
+                    let key = key.as_str()?;
+                    let (name, version) = pnpm_package(key)?;
+                    Some((name, version, key.to_string()))
+                })
+                .collect()
+        }
+        "yarn.lock" => yarn_packages(contents),
+        "Gemfile.lock" => gem_packages(contents),
+        "go.sum" => go_modules(contents),
+        _ => Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    let mut lines = LineIndex::new(contents);
+    Ok(packages
+        .into_iter()
+        .filter(|(name, version, _)| seen.insert((name.clone(), version.clone())))
+        .map(|(name, version, needle)| LockedDependency {
+            file: path.to_path_buf(),
+            line: lines.find(&needle),
+            dependency: Dependency {
+                ecosystem,
+                name,
+                version,
+            },
+        })
+        .collect())
+}
+
+/// Packages of a `package-lock.json`, from the `packages` map of lockfile
+/// versions 2 and 3, or the nested `dependencies` of version 1.
+fn npm_packages(document: &Value) -> Vec<(String, String, String)> {
+    if let Some(packages) = document.get("packages").and_then(Value::as_object) {
+        return packages
+            .iter()
+            .filter(|(path, package)| {
+                // Other keys are the root project and workspace members.
+                path.contains("node_modules/")
+                    && package.get("link").and_then(Value::as_bool) != Some(true)
+            })
+            .filter_map(|(path, package)| {
+                let name = package
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .or_else(|| path.rsplit("node_modules/").next())?;
+                let version = package.get("version")?.as_str()?;
+                Some((name.to_string(), version.to_string(), format!("\"{path}\"")))
+            })
+            .collect();
+    }
+
+    fn walk(dependencies: &Value, packages: &mut Vec<(String, String, String)>) {
+        let Some(dependencies) = dependencies.as_object() else {
+            return;
+        };
+        for (name, package) in dependencies {
+            if let Some(version) = package.get("version").and_then(Value::as_str)
+                && !version.starts_with("file:")
+            {
+                packages.push((name.clone(), version.to_string(), format!("\"{name}\"")));
+            }
+            if let Some(nested) = package.get("dependencies") {
+                walk(nested, packages);
+            }
+        }
+    }
+    let mut packages = Vec::new();
+    if let Some(dependencies) = document.get("dependencies") {
+        walk(dependencies, &mut packages);
+    }
+    packages
+}
+
+/// Splits a pnpm package key into name and version. Keys look like
+/// `/lodash@4.17.21` (v6), `lodash@4.17.21` (v9), `/@scope/pkg@1.0.0(peer@2.0.0)`
+/// or `/lodash/4.17.21` (v5).
+fn pnpm_package(key: &str) -> Option<(String, String)> {
+    let key = key.trim_start_matches('/');
+    let key = key.split('(').next().unwrap_or(key);
+    let (name, version) = match key.get(1..)?.rfind('@') {
+        Some(at) => (&key[..at + 1], &key[at + 2..]),
+        None => key.rsplit_once('/')?,
+    };
+    let version = version.split('_').next().unwrap_or(version);
+    Some((name.to_string(), version.to_string()))
+}
+
+/// Packages of a `yarn.lock`, in both the classic and the Berry format.
+fn yarn_packages(contents: &str) -> Vec<(String, String, String)> {
+    let mut packages = Vec::new();
+    let mut current: Option<(String, String)> = None;
+
+    for line in contents.lines() {
+        if !line.starts_with(' ') && line.ends_with(':') && !line.starts_with('#') {
+            let header = line.trim_end_matches(':');
+            let specifier = header
+                .split(", ")
+                .next()
+                .unwrap_or(header)
+                .trim_matches('"');
+            current = specifier
+                .get(1..)
+                .and_then(|rest| rest.find('@'))
+                .map(|at| (specifier[..at + 1].to_string(), line.to_string()))
+                .filter(|(name, _)| name != "__metadata");
+            continue;
+        }
+        let trimmed = line.trim();
+        let version = trimmed
+            .strip_prefix("version ")
+            .or_else(|| trimmed.strip_prefix("version: "))
+            .map(|v| v.trim().trim_matches('"'));
+        if let (Some(version), Some((name, header))) = (version, current.take()) {
+            // Berry marks workspace packages with a placeholder version.
+            if !version.ends_with("-use.local") {
+                packages.push((name, version.to_string(), header));
+            }
+        }
+    }
+
+    packages
+}
+
+/// Gems of the `GEM` section of a `Gemfile.lock`, without platform suffixes.
+fn gem_packages(contents: &str) -> Vec<(String, String, String)> {
+    let mut packages = Vec::new();
+    let mut in_gems = false;
+
+    for line in contents.lines() {
+        if !line.starts_with(' ') {
+            in_gems = line == "GEM";
+            continue;
+        }
+        // Gems are indented by four spaces; their own dependencies by six.
+        let Some(spec) = line.strip_prefix("    ") else {
+            continue;
+        };
+        if !in_gems || spec.starts_with(' ') {
+            continue;
+        }
+        if let Some((name, version)) = spec.split_once(" (") {
+            let version = version.trim_end_matches(')');
+            let version = version.split('-').next().unwrap_or(version);
+            packages.push((name.to_string(), version.to_string(), line.to_string()));
+        }
+    }
+
+    packages
+}
+
+/// Modules of a `go.sum`. It keeps the checksums of every version ever
+/// resolved, so only the newest version whose source is locked is kept;
+/// `/go.mod`-only entries were needed for resolution but are not built.
+fn go_modules(contents: &str) -> Vec<(String, String, String)> {
+    let mut modules: BTreeMap<&str, &str> = BTreeMap::new();
+
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(module), Some(version)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if version.ends_with("/go.mod") {
+            continue;
+        }
+        let newer = modules
+            .get(module)
+            .is_none_or(|current| compare_versions(version, current).is_gt());
+        if newer {
+            modules.insert(module, version);
+        }
+    }
+
+    modules
+        .into_iter()
+        .map(|(module, version)| {
+            (
+                module.to_string(),
+                version.to_string(),
+                format!("{module} {version} "),
+            )
+        })
+        .collect()
+}
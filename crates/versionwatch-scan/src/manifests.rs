@@ -0,0 +1,153 @@
+use crate::images::image_finding;
+use crate::{Error, Finding, LineIndex, read_file};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// Keys holding container lists in Kubernetes pod specs.
+const CONTAINER_KEYS: &[&str] = &["containers", "initContainers", "ephemeralContainers"];
+
+/// Tells whether a file is a YAML manifest worth scanning.
+pub fn is_manifest(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yml") | Some("yaml")
+    )
+}
+
+/// Scans a docker-compose file, Kubernetes manifest or Helm values file for images.
+pub fn scan_file(path: &Path) -> Result<Vec<Finding>, Error> {
+    let contents = read_file(path)?;
+    Ok(parse(path, &contents))
+}
+
+/// Extracts image references from every YAML document in the file.
+///
+/// Documents that do not parse (such as Helm templates) are skipped.
+pub fn parse(path: &Path, contents: &str) -> Vec<Finding> {
+    let helm_values = is_helm_values(path);
+    let mut lines = LineIndex::new(contents);
+    let mut findings = Vec::new();
+
+    for document in serde_yaml::Deserializer::from_str(contents) {
+        let Ok(value) = Value::deserialize(document) else {
+            continue;
+        };
+
+        if let Some(kind) = value.get("kind").and_then(Value::as_str) {
+            if value.get("apiVersion").is_some() {
+                let mut images = Vec::new();
+                container_images(&value, &mut images);
+                for image in images {
+                    let line = lines.find(&image);
+                    findings.push(image_finding(path, line, kind, &image));
+                }
+            }
+        } else if let Some(services) = value.get("services").and_then(Value::as_mapping) {
+            for service in services.values() {
+                if let Some(image) = service.get("image").and_then(Value::as_str) {
+                    let line = lines.find(image);
+                    findings.push(image_finding(path, line, "compose", image));
+                }
+            }
+        } else if helm_values {
+            helm_images(&value, &mut lines, path, &mut findings);
+        }
+    }
+
+    findings
+}
+
+/// Helm values files are named `values*.yaml`, or sit next to a `Chart.yaml`.
+fn is_helm_values(path: &Path) -> bool {
+    let is_values = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with("values"));
+    let in_chart = path
+        .parent()
+        .is_some_and(|dir| dir.join("Chart.yaml").exists());
+    is_values || in_chart
+}
+
+fn container_images(value: &Value, images: &mut Vec<String>) {
+    match value {
+        Value::Mapping(map) => {
+            for (key, value) in map {
+                let is_containers = key.as_str().is_some_and(|k| CONTAINER_KEYS.contains(&k));
+                match value.as_sequence() {
+                    Some(containers) if is_containers => images.extend(
+                        containers
+                            .iter()
+                            .filter_map(|c| c.get("image").and_then(Value::as_str))
+                            .map(str::to_string),
+                    ),
+                    _ => container_images(value, images),
+                }
+            }
+        }
+        Value::Sequence(values) => {
+            for value in values {
+                container_images(value, images);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Finds `image: repo:tag` strings and the `image.repository`/`image.tag`
+/// (optionally `image.registry`) convention used by most charts.
+fn helm_images(value: &Value, lines: &mut LineIndex, path: &Path, findings: &mut Vec<Finding>) {
+    match value {
+        Value::Mapping(map) => {
+            for (key, value) in map {
+                if key.as_str() != Some("image") {
+                    helm_images(value, lines, path, findings);
+                    continue;
+                }
+                match value {
+                    Value::String(image) => {
+                        let line = lines.find(image);
+                        findings.push(image_finding(path, line, "helm", image));
+                    }
+                    Value::Mapping(image) => {
+                        if let Some(finding) = helm_image(image, lines, path) {
+                            findings.push(finding);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Value::Sequence(values) => {
+            for value in values {
+                helm_images(value, lines, path, findings);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn helm_image(image: &Mapping, lines: &mut LineIndex, path: &Path) -> Option<Finding> {
+    let repository = image.get("repository").and_then(Value::as_str)?;
+    let line = lines.find_entry("repository", repository);
+
+    // Read the tag as written: YAML would turn `tag: 3.10` into the float 3.1.
+    let tag = line
+        .and_then(|line| lines.raw_value_after(line, "tag"))
+        .or_else(|| match image.get("tag")? {
+            Value::String(tag) => Some(tag.clone()),
+            Value::Number(tag) => Some(tag.to_string()),
+            _ => None,
+        });
+
+    let mut reference = match image.get("registry").and_then(Value::as_str) {
+        Some(registry) if !registry.is_empty() => format!("{registry}/{repository}"),
+        _ => repository.to_string(),
+    };
+    if let Some(tag) = tag.filter(|t| !t.is_empty()) {
+        reference = format!("{reference}:{tag}");
+    }
+
+    Some(image_finding(path, line.unwrap_or(1), "helm", &reference))
+}
@@ -0,0 +1,123 @@
+use crate::Finding;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use versionwatch_core::domain::lifecycle::{Lifecycle, SupportStatus, is_behind};
+use versionwatch_core::domain::product_cycle::ProductCycle;
+
+/// Lifecycle status of a scanned reference.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScanStatus {
+    Supported,
+    /// Still supported, but the EOL date falls within the warning window.
+    NearEol,
+    Eol,
+    /// The version could not be matched against collected data, or its cycle
+    /// was superseded and has no EOL date.
+    Unknown,
+    /// The reference does not map to a tracked product.
+    Untracked,
+}
+
+impl ScanStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScanStatus::Supported => "supported",
+            ScanStatus::NearEol => "near EOL",
+            ScanStatus::Eol => "EOL",
+            ScanStatus::Unknown => "unknown",
+            ScanStatus::Untracked => "untracked",
+        }
+    }
+}
+
+/// A finding together with its lifecycle evaluation.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FindingReport {
+    #[serde(flatten)]
+    pub finding: Finding,
+    pub status: ScanStatus,
+    pub cycle: Option<String>,
+    pub eol_date: Option<NaiveDate>,
+    /// Latest release of the cycle, keeping the finding's variant, e.g. "18.20.4-alpine"
+    pub latest: Option<String>,
+    /// Whether a newer patch release of the same cycle exists
+    pub outdated: bool,
+}
+
+/// Evaluates findings against collected cycles, keyed by product name.
+///
+/// Cycles whose EOL date is at most `near_eol_days` away are reported as near EOL.
+pub fn evaluate(
+    findings: Vec<Finding>,
+    cycles: &HashMap<String, Vec<ProductCycle>>,
+    today: NaiveDate,
+    near_eol_days: i64,
+) -> Vec<FindingReport> {
+    let mut lifecycles: HashMap<String, Lifecycle> = HashMap::new();
+
+    findings
+        .into_iter()
+        .map(|finding| {
+            let mut report = FindingReport {
+                status: ScanStatus::Unknown,
+                cycle: None,
+                eol_date: None,
+                latest: None,
+                outdated: false,
+                finding,
+            };
+
+            let Some(product) = report.finding.product.clone() else {
+                report.status = ScanStatus::Untracked;
+                return report;
+            };
+            let Some(rows) = cycles.get(&product) else {
+                return report;
+            };
+            let lifecycle = lifecycles
+                .entry(product.clone())
+                .or_insert_with(|| Lifecycle::new(&product, rows));
+            let Some(version) = report.finding.version.clone() else {
+                return report;
+            };
+            let Some(cycle) = lifecycle.cycle_for_version(&version) else {
+                return report;
+            };
+
+            let near_eol = cycle
+                .eol_date
+                .is_some_and(|eol| (eol - today).num_days() <= near_eol_days);
+            report.status = match lifecycle.status(cycle, today) {
+                SupportStatus::Eol => ScanStatus::Eol,
+                SupportStatus::Unknown => ScanStatus::Unknown,
+                _ if near_eol => ScanStatus::NearEol,
+                _ => ScanStatus::Supported,
+            };
+            report.cycle = Some(cycle.name.clone());
+            report.eol_date = cycle.eol_date;
+            report.outdated = is_behind(&version, &cycle.latest);
+            report.latest = Some(latest_tag(
+                &product,
+                &cycle.latest,
+                report.finding.variant.as_deref(),
+            ));
+            report
+        })
+        .collect()
+}
+
+/// Renders the latest release the way image tags spell it, keeping the variant.
+fn latest_tag(product: &str, latest: &str, variant: Option<&str>) -> String {
+    let variant = variant.unwrap_or_default();
+    if product == "eclipse-temurin" {
+        // Temurin tags spell "17.0.12+7" as "17.0.12_7"; drop the old build number.
+        let variant = variant
+            .strip_prefix('_')
+            .map(|v| v.trim_start_matches(|c: char| c.is_ascii_digit()))
+            .unwrap_or(variant);
+        return format!("{}{variant}", latest.replace('+', "_"));
+    }
+    format!("{latest}{variant}")
+}
@@ -0,0 +1,258 @@
+use crate::images::split_version;
+use crate::{Error, Finding, line_of, read_file};
+use regex::Regex;
+use serde_json::Value;
+use std::path::Path;
+use versionwatch_core::domain::lifecycle::{compare_versions, cycle_key};
+
+/// Files that pin a runtime version.
+const RUNTIME_FILES: &[&str] = &[
+    ".nvmrc",
+    ".node-version",
+    "package.json",
+    ".python-version",
+    "pyproject.toml",
+    ".ruby-version",
+    "Gemfile",
+    "go.mod",
+    "rust-toolchain.toml",
+    "rust-toolchain",
+    "composer.json",
+    ".java-version",
+];
+
+/// Node.js LTS codenames as used by `lts/<name>` aliases in `.nvmrc`.
+const NODE_LTS_CODENAMES: &[(&str, &str)] = &[
+    ("argon", "4"),
+    ("boron", "6"),
+    ("carbon", "8"),
+    ("dubnium", "10"),
+    ("erbium", "12"),
+    ("fermium", "14"),
+    ("gallium", "16"),
+    ("hydrogen", "18"),
+    ("iron", "20"),
+    ("jod", "22"),
+    ("krypton", "24"),
+];
+
+/// Tells whether a file pins a runtime version.
+pub fn is_runtime_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| RUNTIME_FILES.contains(&name))
+}
+
+/// Scans a runtime pin file for the runtime versions it declares.
+pub fn scan_file(path: &Path) -> Result<Vec<Finding>, Error> {
+    let contents = read_file(path)?;
+    Ok(parse(path, &contents))
+}
+
+/// Extracts runtime versions from a pin file, picking the format from its name.
+///
+/// Files that do not parse yield no findings.
+pub fn parse(path: &Path, contents: &str) -> Vec<Finding> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let mut findings = Vec::new();
+    let mut push = |line: usize, source: &str, product: &str, reference: &str| {
+        findings.push(runtime_finding(
+            path,
+            line,
+            source,
+            Some(product),
+            reference,
+        ));
+    };
+
+    match name {
+        ".nvmrc" | ".node-version" => {
+            if let Some((line, value)) = first_value(contents) {
+                push(line, name, "node", value);
+            }
+        }
+        ".python-version" => {
+            if let Some((line, value)) = first_value(contents) {
+                push(line, name, "python", value);
+            }
+        }
+        ".ruby-version" => {
+            if let Some((line, value)) = first_value(contents) {
+                push(line, name, "ruby", value);
+            }
+        }
+        ".java-version" => {
+            if let Some((line, value)) = first_value(contents) {
+                push(line, name, "eclipse-temurin", value);
+            }
+        }
+        "rust-toolchain" => {
+            // The legacy format is either a bare channel or TOML.
+            if let Some((line, value)) = first_value(contents)
+                && !value.starts_with('[')
+            {
+                push(line, "channel", "rust", value);
+            } else if let Some(channel) = rust_channel(contents) {
+                push(line_of(contents, "channel"), "channel", "rust", &channel);
+            }
+        }
+        "rust-toolchain.toml" => {
+            if let Some(channel) = rust_channel(contents) {
+                push(line_of(contents, "channel"), "channel", "rust", &channel);
+            }
+        }
+        "package.json" => {
+            if let Some(node) = json_string(contents, &["engines", "node"]) {
+                push(line_of(contents, "\"node\""), "engines.node", "node", &node);
+            }
+        }
+        "composer.json" => {
+            if let Some(php) = json_string(contents, &["require", "php"]) {
+                push(line_of(contents, "\"php\""), "require.php", "php", &php);
+            }
+        }
+        "pyproject.toml" => {
+            let Ok(document) = contents.parse::<toml::Table>() else {
+                return findings;
+            };
+            let requires = document
+                .get("project")
+                .and_then(|p| p.get("requires-python"))
+                .and_then(|v| v.as_str());
+            let poetry = document
+                .get("tool")
+                .and_then(|t| t.get("poetry"))
+                .and_then(|p| p.get("dependencies"))
+                .and_then(|d| d.get("python"))
+                .and_then(|v| v.as_str());
+            if let Some(requires) = requires {
+                let line = line_of(contents, "requires-python");
+                push(line, "requires-python", "python", requires);
+            } else if let Some(python) = poetry {
+                let line = line_of(contents, "python =");
+                push(line, "tool.poetry.dependencies.python", "python", python);
+            }
+        }
+        "Gemfile" => {
+            let re = Regex::new(r#"^\s*ruby\s*\(?\s*["']([^"']+)["']"#).unwrap();
+            for (index, line) in contents.lines().enumerate() {
+                if let Some(captures) = re.captures(line) {
+                    push(index + 1, "ruby", "ruby", &captures[1]);
+                    break;
+                }
+            }
+        }
+        "go.mod" => {
+            for (index, line) in contents.lines().enumerate() {
+                let mut words = line.split_whitespace();
+                match (words.next(), words.next()) {
+                    (Some("go"), Some(version)) => push(index + 1, "go", "go", version),
+                    (Some("toolchain"), Some(version)) => {
+                        push(index + 1, "toolchain", "go", version)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+
+    findings
+}
+
+/// Builds a finding for a runtime pin, resolving ranges to a version.
+pub(crate) fn runtime_finding(
+    file: &Path,
+    line: usize,
+    source: &str,
+    product: Option<&str>,
+    reference: &str,
+) -> Finding {
+    Finding {
+        file: file.to_path_buf(),
+        line,
+        source: source.to_string(),
+        reference: reference.to_string(),
+        product: product.map(str::to_string),
+        version: resolve(product.unwrap_or_default(), reference),
+        variant: None,
+    }
+}
+
+/// Resolves a pinned version or version range to the version it stands for.
+///
+/// Ranges resolve to the oldest version they admit: that is the oldest runtime
+/// the project claims to support, so it is the one whose lifecycle matters.
+/// Alternatives (`^8.1 || ^8.2`) resolve to the oldest of their lower bounds.
+pub fn resolve(product: &str, reference: &str) -> Option<String> {
+    let reference = reference.trim();
+    if product == "node"
+        && let Some(codename) = reference.strip_prefix("lts/")
+    {
+        return NODE_LTS_CODENAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(codename))
+            .map(|(_, version)| version.to_string());
+    }
+
+    let spaced = Regex::new(r"(>=|<=|~>|==|!=|\^|~|>|<|=)\s+").unwrap();
+    reference
+        .split("||")
+        .filter_map(|alternative| {
+            spaced
+                .replace_all(alternative, "$1")
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter_map(|comparator| lower_bound(product, comparator))
+                .max_by(|a, b| compare_versions(a, b))
+        })
+        .min_by(|a, b| compare_versions(a, b))
+}
+
+/// The lower bound of a single comparator, e.g. "18" for ">=18.2.0".
+///
+/// Open-ended comparators only pin the cycle, so they resolve to the cycle
+/// rather than to a release that would always look outdated.
+fn lower_bound(product: &str, comparator: &str) -> Option<String> {
+    let comparator = comparator.trim();
+    if comparator.starts_with('<') || comparator.starts_with("!=") {
+        return None;
+    }
+    let version = comparator.trim_start_matches(['>', '=', '^', '~']);
+    let open_ended = comparator.len() != version.len() && !comparator.starts_with('=');
+    // Strip distribution prefixes such as "ruby-3.2.2", "go1.21.3" or "temurin-17".
+    let version = version
+        .find(|c: char| c.is_ascii_digit())
+        .map_or(version, |start| &version[start..]);
+    let (version, _) = split_version(version)?;
+
+    if open_ended {
+        return cycle_key(product, &version).or(Some(version));
+    }
+    Some(version)
+}
+
+/// First non-empty, non-comment line of a single-value pin file.
+fn first_value(contents: &str) -> Option<(usize, &str)> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .find(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn rust_channel(contents: &str) -> Option<String> {
+    let document = contents.parse::<toml::Table>().ok()?;
+    let channel = document.get("toolchain")?.get("channel")?.as_str()?;
+    Some(channel.to_string())
+}
+
+fn json_string(contents: &str, path: &[&str]) -> Option<String> {
+    let document: Value = serde_json::from_str(contents).ok()?;
+    path.iter()
+        .try_fold(&document, |value, key| value.get(key))?
+        .as_str()
+        .map(str::to_string)
+}
@@ -0,0 +1,584 @@
+use crate::images::{ImageReference, split_version};
+use crate::report::FindingReport;
+use crate::{Error, Finding, LineIndex, escape_xml, read_file};
+use chrono::NaiveDate;
+use serde_json::{Map, Value, json};
+use std::path::{Path, PathBuf};
+
+/// Prefix of the properties added by [`Sbom::enrich`].
+pub const PROPERTY_PREFIX: &str = "versionwatch:";
+
+/// Package names, as found in SBOMs, and the tracked product they ship.
+const NAME_PRODUCTS: &[(&str, &str)] = &[
+    ("apache2", "apache"),
+    ("caddy", "caddy"),
+    ("cpython", "python"),
+    ("docker", "docker"),
+    ("docker-ce", "docker"),
+    ("docker-engine", "docker"),
+    ("eclipse-temurin", "eclipse-temurin"),
+    ("go", "go"),
+    ("golang", "go"),
+    ("httpd", "apache"),
+    ("kong", "kong"),
+    ("kotlin", "kotlin"),
+    ("kotlin-stdlib", "kotlin"),
+    ("mongodb", "mongodb"),
+    ("mongodb-org-server", "mongodb"),
+    ("mysql", "mysql"),
+    ("mysql-server", "mysql"),
+    ("nginx", "nginx"),
+    ("node", "node"),
+    ("nodejs", "node"),
+    ("perl", "perl"),
+    ("php", "php"),
+    ("postgres", "postgresql"),
+    ("postgresql", "postgresql"),
+    ("python", "python"),
+    ("ruby", "ruby"),
+    ("rust", "rust"),
+    ("rustc", "rust"),
+    ("scala", "scala"),
+    ("scala-library", "scala"),
+    ("swift", "swift"),
+    ("temurin", "eclipse-temurin"),
+];
+
+/// Package URL types of OS and generic packages, whose names are matched to
+/// products. Other types are language libraries: `pkg:npm/mongodb` is the
+/// Node.js driver, not the MongoDB server.
+const NAMED_PURL_TYPES: &[&str] = &["apk", "deb", "generic", "rpm"];
+
+/// Maven coordinates of the artifacts that ship a tracked language runtime.
+const MAVEN_PRODUCTS: &[(&str, &str)] = &[
+    ("org.jetbrains.kotlin/kotlin-stdlib", "kotlin"),
+    ("org.scala-lang/scala-library", "scala"),
+];
+
+/// CPE vendor and product pairs and the tracked product they identify.
+const CPE_PRODUCTS: &[(&str, &str, &str)] = &[
+    ("apache", "http_server", "apache"),
+    ("apple", "swift", "swift"),
+    ("caddyserver", "caddy", "caddy"),
+    ("docker", "docker", "docker"),
+    ("eclipse", "temurin", "eclipse-temurin"),
+    ("f5", "nginx", "nginx"),
+    ("golang", "go", "go"),
+    ("jetbrains", "kotlin", "kotlin"),
+    ("konghq", "kong_gateway", "kong"),
+    ("mongodb", "mongodb", "mongodb"),
+    ("nginx", "nginx", "nginx"),
+    ("nodejs", "node.js", "node"),
+    ("oracle", "mysql", "mysql"),
+    ("perl", "perl", "perl"),
+    ("php", "php", "php"),
+    ("postgresql", "postgresql", "postgresql"),
+    ("python", "python", "python"),
+    ("ruby-lang", "ruby", "ruby"),
+    ("rust-lang", "rust", "rust"),
+    ("scala-lang", "scala", "scala"),
+];
+
+/// CycloneDX XML elements that must follow `properties` inside a component.
+const XML_AFTER_PROPERTIES: &[&str] = &[
+    "components",
+    "evidence",
+    "releaseNotes",
+    "modelCard",
+    "data",
+    "cryptoProperties",
+];
+
+/// Supported SBOM formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomFormat {
+    CycloneDxJson,
+    CycloneDxXml,
+    SpdxJson,
+}
+
+impl SbomFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDxJson | SbomFormat::CycloneDxXml => "cyclonedx",
+            SbomFormat::SpdxJson => "spdx",
+        }
+    }
+}
+
+/// A component listed in an SBOM, with the identifiers used to match it.
+#[derive(Debug, Clone, Default)]
+pub struct Component {
+    pub name: String,
+    pub version: Option<String>,
+    pub purl: Option<String>,
+    pub cpes: Vec<String>,
+}
+
+impl Component {
+    /// The tracked product this component is, and its version, if recognized.
+    ///
+    /// Package URLs are the most precise identifier, then CPEs, then the name.
+    pub fn product(&self) -> Option<(&'static str, Option<String>)> {
+        let from_purl = self.purl.as_deref().and_then(purl_product);
+        let from_cpe = || self.cpes.iter().find_map(|cpe| cpe_product(cpe));
+        // A library's name says nothing about the product it is named after.
+        let named = match self.purl.as_deref() {
+            Some(purl) => purl_type(purl).is_some_and(|t| NAMED_PURL_TYPES.contains(&t)),
+            None => true,
+        };
+        let from_name = || {
+            let product = if named {
+                name_product(&self.name)
+            } else {
+                None
+            };
+            product.map(|p| (p, None))
+        };
+
+        let (product, version) = from_purl.or_else(from_cpe).or_else(from_name)?;
+        let version = version
+            .or_else(|| self.version.clone())
+            .and_then(|v| normalize_version(&v));
+        Some((product, version))
+    }
+
+    fn reference(&self) -> String {
+        match (&self.purl, self.cpes.first(), &self.version) {
+            (Some(purl), _, _) => purl.clone(),
+            (None, Some(cpe), _) => cpe.clone(),
+            (None, None, Some(version)) => format!("{}@{version}", self.name),
+            (None, None, None) => self.name.clone(),
+        }
+    }
+}
+
+/// A parsed SBOM document.
+pub struct Sbom {
+    path: PathBuf,
+    contents: String,
+    format: SbomFormat,
+}
+
+impl Sbom {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = read_file(path)?;
+        Self::parse(path, contents)
+    }
+
+    /// Detects the format from the document itself rather than the file name.
+    pub fn parse(path: &Path, contents: String) -> Result<Self, Error> {
+        let format = if contents.trim_start().starts_with('<') {
+            let document =
+                roxmltree::Document::parse(&contents).map_err(|e| parse_error(path, e))?;
+            if !document.root_element().has_tag_name("bom") {
+                return Err(parse_error(path, "not a CycloneDX document"));
+            }
+            SbomFormat::CycloneDxXml
+        } else {
+            let document: Value =
+                serde_json::from_str(&contents).map_err(|e| parse_error(path, e))?;
+            if document.get("bomFormat").and_then(Value::as_str) == Some("CycloneDX") {
+                SbomFormat::CycloneDxJson
+            } else if document.get("spdxVersion").is_some() {
+                SbomFormat::SpdxJson
+            } else {
+                return Err(parse_error(path, "not a CycloneDX or SPDX document"));
+            }
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            contents,
+            format,
+        })
+    }
+
+    pub fn format(&self) -> SbomFormat {
+        self.format
+    }
+
+    /// All components, in document order, including nested CycloneDX components.
+    pub fn components(&self) -> Vec<Component> {
+        match self.format {
+            SbomFormat::CycloneDxJson => {
+                let mut document: Value = serde_json::from_str(&self.contents).unwrap_or_default();
+                let mut components = Vec::new();
+                visit_cyclonedx(&mut document, &mut |c| components.push(json_component(c)));
+                components
+            }
+            SbomFormat::SpdxJson => {
+                let mut document: Value = serde_json::from_str(&self.contents).unwrap_or_default();
+                let mut components = Vec::new();
+                visit_spdx(&mut document, &mut |p| components.push(spdx_component(p)));
+                components
+            }
+            SbomFormat::CycloneDxXml => match roxmltree::Document::parse(&self.contents) {
+                Ok(document) => xml_components(&document)
+                    .map(|c| xml_component(&c))
+                    .collect(),
+                Err(_) => Vec::new(),
+            },
+        }
+    }
+
+    /// One finding per component matched to a tracked product, in document order.
+    pub fn findings(&self) -> Vec<Finding> {
+        let mut lines = LineIndex::new(&self.contents);
+
+        self.components()
+            .into_iter()
+            .filter_map(|component| {
+                let (product, version) = component.product()?;
+                let reference = component.reference();
+                let needle = component.purl.as_deref().unwrap_or(&component.name);
+                Some(Finding {
+                    file: self.path.clone(),
+                    line: lines.find(needle),
+                    source: self.format.label().to_string(),
+                    reference,
+                    product: Some(product.to_string()),
+                    version,
+                    variant: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the document with lifecycle properties added to every matched
+    /// component: `versionwatch:cycle`, `versionwatch:support_status`,
+    /// `versionwatch:eol_date` and `versionwatch:latest_version`.
+    ///
+    /// `reports` must be the evaluation of [`Sbom::findings`], in the same order.
+    /// Properties from a previous run are replaced. SPDX has no component
+    /// properties, so they are written as package annotations dated `today`.
+    pub fn enrich(&self, reports: &[FindingReport], today: NaiveDate) -> Result<String, Error> {
+        let mut reports = reports.iter();
+        let mut next_report = |component: &Component| {
+            component.product()?;
+            reports.next()
+        };
+
+        match self.format {
+            SbomFormat::CycloneDxJson => {
+                let mut document: Value =
+                    serde_json::from_str(&self.contents).map_err(|e| parse_error(&self.path, e))?;
+                visit_cyclonedx(&mut document, &mut |component| {
+                    if let Some(report) = next_report(&json_component(component)) {
+                        let properties = component
+                            .entry("properties")
+                            .or_insert_with(|| Value::Array(Vec::new()));
+                        if let Value::Array(properties) = properties {
+                            properties.retain(|p| !is_own_property(p.get("name")));
+                            properties.extend(
+                                lifecycle_properties(report)
+                                    .into_iter()
+                                    .map(|(name, value)| json!({ "name": name, "value": value })),
+                            );
+                        }
+                    }
+                });
+                to_json(&self.path, &document)
+            }
+            SbomFormat::SpdxJson => {
+                let mut document: Value =
+                    serde_json::from_str(&self.contents).map_err(|e| parse_error(&self.path, e))?;
+                visit_spdx(&mut document, &mut |package| {
+                    if let Some(report) = next_report(&spdx_component(package)) {
+                        let annotations = package
+                            .entry("annotations")
+                            .or_insert_with(|| Value::Array(Vec::new()));
+                        if let Value::Array(annotations) = annotations {
+                            annotations.retain(|a| !is_own_property(a.get("comment")));
+                            annotations.extend(lifecycle_properties(report).into_iter().map(
+                                |(name, value)| {
+                                    json!({
+                                        "annotationDate": format!("{today}T00:00:00Z"),
+                                        "annotationType": "OTHER",
+                                        "annotator": "Tool: versionwatch",
+                                        "comment": format!("{name}={value}"),
+                                    })
+                                },
+                            ));
+                        }
+                    }
+                });
+                to_json(&self.path, &document)
+            }
+            SbomFormat::CycloneDxXml => {
+                let document = roxmltree::Document::parse(&self.contents)
+                    .map_err(|e| parse_error(&self.path, e))?;
+                let mut edits = Vec::new();
+                for node in xml_components(&document) {
+                    if let Some(report) = next_report(&xml_component(&node)) {
+                        edits.extend(xml_property_edits(&self.contents, &node, report));
+                    }
+                }
+                Ok(apply_edits(&self.contents, edits))
+            }
+        }
+    }
+}
+
+/// Name/value pairs describing a component's lifecycle.
+fn lifecycle_properties(report: &FindingReport) -> Vec<(String, String)> {
+    let status = serde_json::to_value(report.status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let mut properties = vec![(format!("{PROPERTY_PREFIX}support_status"), status)];
+    if let Some(cycle) = &report.cycle {
+        properties.push((format!("{PROPERTY_PREFIX}cycle"), cycle.clone()));
+    }
+    if let Some(eol) = report.eol_date {
+        properties.push((format!("{PROPERTY_PREFIX}eol_date"), eol.to_string()));
+    }
+    if let Some(latest) = &report.latest {
+        properties.push((format!("{PROPERTY_PREFIX}latest_version"), latest.clone()));
+    }
+    properties
+}
+
+fn is_own_property(name: Option<&Value>) -> bool {
+    name.and_then(Value::as_str)
+        .is_some_and(|n| n.starts_with(PROPERTY_PREFIX))
+}
+
+fn parse_error(path: &Path, error: impl ToString) -> Error {
+    Error::Parse {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    }
+}
+
+fn to_json(path: &Path, document: &Value) -> Result<String, Error> {
+    serde_json::to_string_pretty(document).map_err(|e| parse_error(path, e))
+}
+
+fn visit_cyclonedx(value: &mut Value, visit: &mut impl FnMut(&mut Map<String, Value>)) {
+    let Some(components) = value.get_mut("components").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for component in components {
+        if let Value::Object(map) = component {
+            visit(map);
+        }
+        visit_cyclonedx(component, visit);
+    }
+}
+
+fn visit_spdx(value: &mut Value, visit: &mut impl FnMut(&mut Map<String, Value>)) {
+    let Some(packages) = value.get_mut("packages").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for package in packages.iter_mut().filter_map(Value::as_object_mut) {
+        visit(package);
+    }
+}
+
+fn string(map: &Map<String, Value>, key: &str) -> Option<String> {
+    map.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn json_component(component: &Map<String, Value>) -> Component {
+    Component {
+        name: string(component, "name").unwrap_or_default(),
+        version: string(component, "version"),
+        purl: string(component, "purl"),
+        cpes: string(component, "cpe").into_iter().collect(),
+    }
+}
+
+fn spdx_component(package: &Map<String, Value>) -> Component {
+    let mut component = Component {
+        name: string(package, "name").unwrap_or_default(),
+        version: string(package, "versionInfo"),
+        ..Default::default()
+    };
+    let references = package.get("externalRefs").and_then(Value::as_array);
+    for reference in references.into_iter().flatten() {
+        let kind = reference.get("referenceType").and_then(Value::as_str);
+        let locator = reference.get("referenceLocator").and_then(Value::as_str);
+        match (kind, locator) {
+            (Some("purl"), Some(purl)) => component.purl = Some(purl.to_string()),
+            (Some(kind), Some(cpe)) if kind.starts_with("cpe") => {
+                component.cpes.push(cpe.to_string())
+            }
+            _ => {}
+        }
+    }
+    component
+}
+
+fn xml_components<'a, 'input>(
+    document: &'a roxmltree::Document<'input>,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    document.descendants().filter(|node| {
+        node.has_tag_name("component")
+            && node.parent().is_some_and(|p| p.has_tag_name("components"))
+    })
+}
+
+fn xml_component(node: &roxmltree::Node) -> Component {
+    let child = |name: &str| {
+        node.children()
+            .find(|c| c.has_tag_name(name))
+            .and_then(|c| c.text())
+            .map(|t| t.trim().to_string())
+    };
+    Component {
+        name: child("name").unwrap_or_default(),
+        version: child("version"),
+        purl: child("purl"),
+        cpes: child("cpe").into_iter().collect(),
+    }
+}
+
+/// Edits replacing previous lifecycle properties of an XML component and adding
+/// the current ones, keeping `properties` where the CycloneDX schema expects it.
+fn xml_property_edits(
+    contents: &str,
+    component: &roxmltree::Node,
+    report: &FindingReport,
+) -> Vec<(usize, usize, String)> {
+    let properties: String = lifecycle_properties(report)
+        .into_iter()
+        .map(|(name, value)| {
+            format!(
+                "<property name=\"{}\">{}</property>",
+                escape_xml(&name),
+                escape_xml(&value)
+            )
+        })
+        .collect();
+
+    let mut edits = Vec::new();
+    let existing = component.children().find(|c| c.has_tag_name("properties"));
+
+    match existing {
+        Some(existing) => {
+            for property in existing.children().filter(|c| {
+                c.has_tag_name("property")
+                    && c.attribute("name")
+                        .is_some_and(|n| n.starts_with(PROPERTY_PREFIX))
+            }) {
+                let range = property.range();
+                edits.push((range.start, range.end, String::new()));
+            }
+            let range = existing.range();
+            match contents[range.clone()].rfind("</") {
+                Some(close) => edits.push((range.start + close, range.start + close, properties)),
+                None => edits.push((
+                    range.start,
+                    range.end,
+                    format!("<properties>{properties}</properties>"),
+                )),
+            }
+        }
+        None => {
+            let range = component.range();
+            let position = component
+                .children()
+                .find(|c| {
+                    XML_AFTER_PROPERTIES
+                        .iter()
+                        .any(|name| c.has_tag_name(*name))
+                })
+                .map(|c| c.range().start)
+                .or_else(|| contents[range.clone()].rfind("</").map(|i| range.start + i))
+                .unwrap_or(range.end);
+            edits.push((
+                position,
+                position,
+                format!("<properties>{properties}</properties>"),
+            ));
+        }
+    }
+
+    edits
+}
+
+fn apply_edits(contents: &str, mut edits: Vec<(usize, usize, String)>) -> String {
+    let mut output = contents.to_string();
+    edits.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+    for (start, end, replacement) in edits {
+        output.replace_range(start..end, &replacement);
+    }
+    output
+}
+
+/// The type of a package URL, e.g. "npm" for `pkg:npm/mongodb@6.0.0`.
+fn purl_type(purl: &str) -> Option<&str> {
+    purl.strip_prefix("pkg:")?
+        .split_once('/')
+        .map(|(kind, _)| kind)
+}
+
+/// Matches a package URL such as `pkg:docker/library/node@18.20`,
+/// `pkg:golang/stdlib@1.22.3` or `pkg:deb/debian/nginx@1.22.1` to a product and
+/// version. Language library packages are not matched.
+fn purl_product(purl: &str) -> Option<(&'static str, Option<String>)> {
+    let purl = purl.strip_prefix("pkg:")?;
+    let purl = purl.split(['?', '#']).next().unwrap_or(purl);
+    let (path, version) = match purl.rsplit_once('@') {
+        Some((path, version)) => (path, Some(version.replace("%2B", "+"))),
+        None => (purl, None),
+    };
+    let (kind, name) = path.split_once('/')?;
+
+    let product = match kind {
+        "docker" | "oci" => ImageReference::parse(name)?.product()?,
+        "golang" if name == "stdlib" => "go",
+        "maven" => MAVEN_PRODUCTS
+            .iter()
+            .find(|(coordinates, _)| *coordinates == name)
+            .map(|(_, product)| *product)?,
+        kind if NAMED_PURL_TYPES.contains(&kind) => {
+            name_product(name.rsplit('/').next().unwrap_or(name))?
+        }
+        _ => return None,
+    };
+    Some((product, version))
+}
+
+/// Matches a CPE 2.3 (`cpe:2.3:a:nodejs:node.js:18.19.0:...`) or CPE 2.2
+/// (`cpe:/a:nodejs:node.js:18.19.0`) name to a product and version.
+fn cpe_product(cpe: &str) -> Option<(&'static str, Option<String>)> {
+    let fields: Vec<&str> = match cpe.strip_prefix("cpe:2.3:") {
+        Some(rest) => rest.split(':').collect(),
+        None => cpe.strip_prefix("cpe:/")?.split(':').collect(),
+    };
+    let (vendor, product) = (*fields.get(1)?, *fields.get(2)?);
+    let version = fields
+        .get(3)
+        .filter(|v| !matches!(**v, "" | "*" | "-"))
+        .map(|v| v.to_string());
+
+    CPE_PRODUCTS
+        .iter()
+        .find(|(v, p, _)| *v == vendor && *p == product)
+        .map(|(_, _, tracked)| (*tracked, version))
+}
+
+/// Matches a package name, ignoring version suffixes as in "postgresql-16" or "python3.12".
+fn name_product(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    let lookup = |name: &str| {
+        NAME_PRODUCTS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, product)| *product)
+    };
+    lookup(&name).or_else(|| {
+        let base = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '-');
+        lookup(base)
+    })
+}
+
+/// Drops Debian-style epochs ("1:15.4-1"), name prefixes ("go1.21.3") and
+/// packaging suffixes ("15.4-1.pgdg120+1").
+fn normalize_version(version: &str) -> Option<String> {
+    let version = version.split_once(':').map_or(version, |(_, v)| v);
+    let start = version.find(|c: char| c.is_ascii_digit())?;
+    split_version(&version[start..]).map(|(version, _)| version)
+}
@@ -0,0 +1,146 @@
+use crate::runtimes::runtime_finding;
+use crate::{Error, Finding, read_file};
+use std::path::Path;
+use toml::Value;
+
+/// asdf and mise plugin names and the tracked product they install.
+const PLUGIN_PRODUCTS: &[(&str, &str)] = &[
+    ("caddy", "caddy"),
+    ("go", "go"),
+    ("golang", "go"),
+    ("kotlin", "kotlin"),
+    ("mongodb", "mongodb"),
+    ("mysql", "mysql"),
+    ("nginx", "nginx"),
+    ("node", "node"),
+    ("nodejs", "node"),
+    ("perl", "perl"),
+    ("php", "php"),
+    ("postgres", "postgresql"),
+    ("postgresql", "postgresql"),
+    ("python", "python"),
+    ("ruby", "ruby"),
+    ("rust", "rust"),
+    ("scala", "scala"),
+    ("swift", "swift"),
+];
+
+/// Java distributions, as prefixed to versions like `temurin-21.0.2+13`, and the
+/// tracked product they correspond to.
+const JAVA_VENDORS: &[(&str, &str)] = &[
+    ("temurin", "eclipse-temurin"),
+    ("adoptopenjdk", "eclipse-temurin"),
+];
+
+/// Tells whether a file is an asdf `.tool-versions` or a mise config file.
+pub fn is_tool_versions(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|n| n.to_str()),
+        Some(".tool-versions") | Some("mise.toml") | Some(".mise.toml")
+    )
+}
+
+/// Scans a `.tool-versions` or `mise.toml` file for pinned tools.
+pub fn scan_file(path: &Path) -> Result<Vec<Finding>, Error> {
+    let contents = read_file(path)?;
+    Ok(parse(path, &contents))
+}
+
+/// Extracts one finding per pinned tool version.
+///
+/// Both formats allow several versions per tool (the first one is active, the
+/// others are fallbacks); each of them is reported. mise files that do not
+/// parse yield no findings.
+pub fn parse(path: &Path, contents: &str) -> Vec<Finding> {
+    if path.extension().is_some_and(|e| e == "toml") {
+        parse_mise(path, contents)
+    } else {
+        parse_asdf(path, contents)
+    }
+}
+
+/// Maps a plugin name and version to the tracked product, if any.
+///
+/// Java is tracked per distribution, so its product depends on the vendor
+/// prefix of the version.
+pub fn plugin_product(plugin: &str, version: &str) -> Option<&'static str> {
+    // mise accepts backend-qualified names such as "core:node" or "asdf:nodejs".
+    let plugin = plugin.rsplit(':').next().unwrap_or(plugin);
+    if plugin == "java" {
+        let (vendor, _) = version.split_once('-')?;
+        return JAVA_VENDORS
+            .iter()
+            .find(|(name, _)| *name == vendor)
+            .map(|(_, product)| *product);
+    }
+    PLUGIN_PRODUCTS
+        .iter()
+        .find(|(name, _)| *name == plugin)
+        .map(|(_, product)| *product)
+}
+
+fn parse_asdf(path: &Path, contents: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut words = line.split_whitespace();
+        let Some(plugin) = words.next() else {
+            continue;
+        };
+        for version in words {
+            let product = plugin_product(plugin, version);
+            findings.push(runtime_finding(path, index + 1, plugin, product, version));
+        }
+    }
+
+    findings
+}
+
+fn parse_mise(path: &Path, contents: &str) -> Vec<Finding> {
+    let Ok(document) = contents.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let Some(tools) = document.get("tools").and_then(Value::as_table) else {
+        return Vec::new();
+    };
+    let mut findings = Vec::new();
+
+    for (plugin, value) in tools {
+        // A tool is pinned as "20", ["20", "18"] or { version = "20" }.
+        let versions: Vec<&str> = match value {
+            Value::String(version) => vec![version.as_str()],
+            Value::Array(versions) => versions.iter().filter_map(Value::as_str).collect(),
+            Value::Table(options) => options
+                .get("version")
+                .and_then(Value::as_str)
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        };
+        let line = tool_line(contents, plugin);
+        for version in versions {
+            let product = plugin_product(plugin, version);
+            findings.push(runtime_finding(path, line, plugin, product, version));
+        }
+    }
+
+    findings
+}
+
+/// 1-based line declaring a tool in the `[tools]` table, or 1 if not found.
+fn tool_line(contents: &str, plugin: &str) -> usize {
+    let mut in_tools = false;
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_tools = line == "[tools]";
+            continue;
+        }
+        let key = line.split('=').next().unwrap_or_default().trim();
+        if in_tools && key.trim_matches(|c| c == '"' || c == '\'') == plugin {
+            return index + 1;
+        }
+    }
+    1
+}
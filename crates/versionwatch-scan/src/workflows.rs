@@ -0,0 +1,228 @@
+use crate::report::{FindingReport, ScanStatus};
+use crate::runtimes::runtime_finding;
+use crate::{Error, Finding, LineIndex, read_file};
+use chrono::NaiveDate;
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// Setup actions, the input holding the version they install, and its product.
+const SETUP_ACTIONS: &[(&str, &str, &str)] = &[
+    ("actions/setup-node", "node-version", "node"),
+    ("actions/setup-python", "python-version", "python"),
+    ("actions/setup-go", "go-version", "go"),
+    ("actions/setup-java", "java-version", "eclipse-temurin"),
+    ("actions/setup-ruby", "ruby-version", "ruby"),
+    ("ruby/setup-ruby", "ruby-version", "ruby"),
+    ("dtolnay/rust-toolchain", "toolchain", "rust"),
+];
+
+/// `actions/setup-java` distributions that install Eclipse Temurin.
+const TEMURIN_DISTRIBUTIONS: &[&str] = &["temurin", "adopt", "adopt-hotspot"];
+
+/// GitHub-hosted runner images and the day GitHub retired them.
+const RETIRED_RUNNERS: &[(&str, &str)] = &[
+    ("ubuntu-16.04", "2021-09-20"),
+    ("ubuntu-18.04", "2023-04-03"),
+    ("ubuntu-20.04", "2025-04-15"),
+    ("windows-2016", "2022-03-15"),
+    ("windows-2019", "2025-06-30"),
+    ("macos-10.15", "2022-12-01"),
+    ("macos-11", "2024-06-28"),
+    ("macos-12", "2024-12-03"),
+    ("macos-13", "2025-12-04"),
+];
+
+/// Tells whether a file is a GitHub Actions workflow.
+pub fn is_workflow(path: &Path) -> bool {
+    let is_yaml = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yml") | Some("yaml")
+    );
+    let in_workflows = path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .is_some_and(|name| name == "workflows");
+    is_yaml && in_workflows
+}
+
+/// Scans a workflow for runner images and tool versions installed by setup actions.
+pub fn scan_file(path: &Path) -> Result<Vec<Finding>, Error> {
+    let contents = read_file(path)?;
+    Ok(parse(path, &contents))
+}
+
+/// Extracts `runs-on` labels and setup action versions from every job.
+///
+/// Values referring to `${{ matrix.* }}` are expanded into one finding per
+/// matrix value. Workflows that do not parse yield no findings.
+pub fn parse(path: &Path, contents: &str) -> Vec<Finding> {
+    let Ok(workflow) = serde_yaml::from_str::<Value>(contents) else {
+        return Vec::new();
+    };
+    let Some(jobs) = workflow.get("jobs").and_then(Value::as_mapping) else {
+        return Vec::new();
+    };
+    let mut lines = LineIndex::new(contents);
+    let mut findings = Vec::new();
+
+    for job in jobs.values() {
+        let matrix = job
+            .get("strategy")
+            .and_then(|s| s.get("matrix"))
+            .and_then(Value::as_mapping);
+
+        if let Some(runs_on) = job.get("runs-on") {
+            let line = lines.find("runs-on");
+            let labels = match runs_on {
+                Value::Sequence(labels) => labels.iter().filter_map(scalar).collect(),
+                value => scalar(value).into_iter().collect::<Vec<_>>(),
+            };
+            for label in labels.iter().flat_map(|l| expand(l, matrix)) {
+                findings.push(runner_finding(path, line, &label));
+            }
+        }
+
+        let steps = job.get("steps").and_then(Value::as_sequence);
+        for step in steps.into_iter().flatten() {
+            let Some(uses) = step.get("uses").and_then(Value::as_str) else {
+                continue;
+            };
+            let (action, git_ref) = uses.split_once('@').unwrap_or((uses, ""));
+            let Some(&(_, input, product)) = SETUP_ACTIONS.iter().find(|(a, _, _)| *a == action)
+            else {
+                continue;
+            };
+            let line = lines.find(uses);
+
+            let product = match action {
+                "actions/setup-java" => {
+                    let distribution = step
+                        .get("with")
+                        .and_then(|w| w.get("distribution"))
+                        .and_then(scalar)
+                        .unwrap_or_default();
+                    TEMURIN_DISTRIBUTIONS
+                        .contains(&distribution.as_str())
+                        .then_some(product)
+                }
+                _ => Some(product),
+            };
+
+            let mut versions = step
+                .get("with")
+                .and_then(|w| w.get(input))
+                .map(input_versions)
+                .unwrap_or_default();
+            // dtolnay/rust-toolchain also takes the toolchain as its ref.
+            if versions.is_empty() && action == "dtolnay/rust-toolchain" && !git_ref.is_empty() {
+                versions.push(git_ref.to_string());
+            }
+
+            for version in versions.iter().flat_map(|v| expand(v, matrix)) {
+                findings.push(runtime_finding(path, line, action, product, &version));
+            }
+        }
+    }
+
+    findings
+}
+
+/// Marks retired GitHub-hosted runner images as EOL, and those retiring within
+/// `near_eol_days` as near EOL. Runner labels are not tracked products, so they
+/// come out of [`crate::report::evaluate`] as untracked.
+pub fn evaluate_runners(reports: &mut [FindingReport], today: NaiveDate, near_eol_days: i64) {
+    for report in reports.iter_mut().filter(|r| r.finding.source == "runs-on") {
+        let retirement = RETIRED_RUNNERS
+            .iter()
+            .find(|(label, _)| *label == report.finding.reference)
+            .and_then(|(_, date)| date.parse::<NaiveDate>().ok());
+        let Some(retirement) = retirement else {
+            continue;
+        };
+        report.eol_date = Some(retirement);
+        report.status = if retirement <= today {
+            ScanStatus::Eol
+        } else if (retirement - today).num_days() <= near_eol_days {
+            ScanStatus::NearEol
+        } else {
+            ScanStatus::Supported
+        };
+    }
+}
+
+fn runner_finding(file: &Path, line: usize, label: &str) -> Finding {
+    Finding {
+        file: file.to_path_buf(),
+        line,
+        source: "runs-on".to_string(),
+        reference: label.to_string(),
+        product: None,
+        version: None,
+        variant: None,
+    }
+}
+
+/// Versions given to a setup action: a scalar, a list, or one version per line.
+fn input_versions(value: &Value) -> Vec<String> {
+    match value {
+        Value::Sequence(values) => values.iter().filter_map(scalar).collect(),
+        value => scalar(value)
+            .map(|v| {
+                v.lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// Replaces `${{ matrix.key }}` with every value the matrix gives `key`.
+///
+/// Values are taken from the key's list and from `include` entries. Expressions
+/// that reference anything else are left as written.
+fn expand(value: &str, matrix: Option<&Mapping>) -> Vec<String> {
+    let re = Regex::new(r"\$\{\{\s*matrix\.([A-Za-z0-9_-]+)\s*\}\}").unwrap();
+    let Some(captures) = re.captures(value) else {
+        return vec![value.to_string()];
+    };
+    let Some(matrix) = matrix else {
+        return vec![value.to_string()];
+    };
+
+    let key = &captures[1];
+    let mut values: Vec<String> = match matrix.get(key) {
+        Some(Value::Sequence(values)) => values.iter().filter_map(scalar).collect(),
+        Some(value) => scalar(value).into_iter().collect(),
+        None => Vec::new(),
+    };
+    let includes = matrix.get("include").and_then(Value::as_sequence);
+    for include in includes.into_iter().flatten() {
+        if let Some(value) = include.get(key).and_then(scalar)
+            && !values.contains(&value)
+        {
+            values.push(value);
+        }
+    }
+    if values.is_empty() {
+        return vec![value.to_string()];
+    }
+
+    let whole = captures.get(0).map_or("", |m| m.as_str());
+    values
+        .iter()
+        .flat_map(|v| expand(&value.replacen(whole, v, 1), Some(matrix)))
+        .collect()
+}
+
+/// Renders a scalar the way GitHub passes it to actions, e.g. `3.10` as "3.1".
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
@@ -2,7 +2,9 @@ export interface DashboardMetrics {
   total_collectors: number
   active_collectors: number
   failed_collectors: number
+  timed_out_collectors: number
   total_versions: number
+  quarantined_rows: number
   last_updated: string
   collector_stats: CollectorStat[]
   system_health: SystemHealth
@@ -16,6 +18,9 @@ export interface CollectorStat {
   performance_category: string
   response_time: number
   error_message?: string
+  source?: string
+  quarantined: number
+  next_run?: string
 }
 
 export interface SystemHealth {
@@ -6,6 +6,11 @@ interface CollectorsProps {
   metrics: DashboardMetrics
 }
 
+const statusColor = (status: string) =>
+  status === 'Active' ? commonStyles.statusColors.active :
+  status === 'Source Degraded' || status === 'Degraded (fallback)' ? commonStyles.statusColors.warning :
+  commonStyles.statusColors.failed
+
 export const Collectors: React.FC<CollectorsProps> = ({ metrics }) => {
   const hasCollectors = metrics?.collector_stats?.length > 0
 
@@ -29,7 +34,7 @@ export const Collectors: React.FC<CollectorsProps> = ({ metrics }) => {
               padding: '1rem',
               backgroundColor: '#f8f9fa',
               borderRadius: '0.5rem',
-              border: `2px solid ${collector.status === 'Active' ? commonStyles.statusColors.active : commonStyles.statusColors.failed}`,
+              border: `2px solid ${statusColor(collector.status)}`,
               display: 'grid',
               gridTemplateColumns: 'auto 1fr auto auto',
               alignItems: 'center',
@@ -40,12 +45,17 @@ export const Collectors: React.FC<CollectorsProps> = ({ metrics }) => {
                 width: '12px',
                 height: '12px',
                 borderRadius: '50%',
-                backgroundColor: collector.status === 'Active' ? commonStyles.statusColors.active : commonStyles.statusColors.failed
+                backgroundColor: statusColor(collector.status)
               }} />
               
               {/* Collector info */}
               <div>
                 <strong style={{ fontSize: '1.1rem' }}>{collector.name}</strong>
+                {collector.status === 'Source Degraded' && (
+                  <span style={{ color: commonStyles.statusColors.warning, marginLeft: '0.5rem' }}>
+                    Source degraded — acknowledge with POST /api/collectors/{collector.name}/acknowledge
+                  </span>
+                )}
                 <div style={{ fontSize: '0.9rem', color: '#666', marginTop: '0.25rem' }}>
                   {collector.version_count} versions • 
                   <span style={{ 
@@ -57,6 +67,12 @@ export const Collectors: React.FC<CollectorsProps> = ({ metrics }) => {
                   }}>
                     {collector.performance_category}
                   </span>
+                  {collector.source && ` • via ${collector.source}`}
+                  {collector.quarantined > 0 && (
+                    <span style={{ color: commonStyles.statusColors.warning, marginLeft: '0.5rem' }}>
+                      • {collector.quarantined} quarantined
+                    </span>
+                  )}
                 </div>
                 {collector.error_message && (
                   <div style={{ fontSize: '0.8rem', color: commonStyles.statusColors.failed, marginTop: '0.25rem' }}>
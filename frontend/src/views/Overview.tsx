@@ -75,6 +75,14 @@ export const Overview: React.FC<OverviewProps> = ({ metrics }) => {
           color="#f44336"
           tooltip="Number of collectors that failed during their last collection attempt"
         />
+
+        <MetricCard
+          title="Timed Out"
+          value={metrics?.timed_out_collectors || 0}
+          icon="⏱️"
+          color="#f44336"
+          tooltip="Failed collectors whose last collection ran past its timeout"
+        />
         
         <MetricCard
           title="Total Versions"
@@ -83,6 +91,14 @@ export const Overview: React.FC<OverviewProps> = ({ metrics }) => {
           color="#FF9800"
           tooltip="Total number of software versions discovered by all active collectors"
         />
+
+        <MetricCard
+          title="Quarantined Rows"
+          value={metrics?.quarantined_rows || 0}
+          icon="🚧"
+          color="#F44336"
+          tooltip="Collected rows held back by validation (see /api/quarantine)"
+        />
       </div>
 
       {/* System Health */}